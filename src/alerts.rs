@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 use crate::game_state::{
-    self, GameState, ResourceType, BASE_STORAGE_CAPACITY, STORAGE_SILO_CAPACITY,
+    self, BuildingStatus, GameState, ResourceType, BASE_STORAGE_CAPACITY, STORAGE_SILO_CAPACITY,
 };
 
 #[derive(Resource, Default)]
@@ -9,6 +9,7 @@ pub struct AlertState {
     food: bool,
     unrest: bool,
     cap: bool,
+    stalled: bool,
 }
 
 pub struct AlertPlugin;
@@ -31,7 +32,7 @@ fn alert_system(mut alert: ResMut<AlertState>, mut game_state: ResMut<GameState>
         alert.power = false;
     }
 
-    let food = *game_state.current_resources.get(&ResourceType::NutrientPaste).unwrap_or(&0.0);
+    let food = game_state.current_resources.get(ResourceType::NutrientPaste);
     if food < 10.0 && !alert.food {
         game_state::add_notification(&mut game_state.notifications, "ALERT: Food shortage.".to_string(), now);
         alert.food = true;
@@ -59,4 +60,25 @@ fn alert_system(mut alert: ResMut<AlertState>, mut game_state: ResMut<GameState>
     } else if !nearing_cap && alert.cap {
         alert.cap = false;
     }
+
+    let statuses = game_state::building_statuses(&game_state);
+    let stalled: Vec<String> = [
+        BuildingStatus::Unpowered,
+        BuildingStatus::MissingInputs,
+        BuildingStatus::OutputStorageFull,
+        BuildingStatus::UnpaidUpkeep,
+    ]
+    .iter()
+    .filter_map(|status| {
+        let count = statuses.iter().filter(|(_, s)| s == status).count();
+        (count > 0).then(|| format!("{} {}", count, status.label()))
+    })
+    .collect();
+    if !stalled.is_empty() && !alert.stalled {
+        game_state::add_notification(&mut game_state.notifications, format!("ALERT: Buildings stalled: {}.", stalled.join(", ")), now);
+        alert.stalled = true;
+    } else if stalled.is_empty() && alert.stalled {
+        game_state::add_notification(&mut game_state.notifications, "All buildings back in operation.".to_string(), now);
+        alert.stalled = false;
+    }
 }
\ No newline at end of file
@@ -5,3 +5,39 @@ pub struct Specialist;
 
 #[derive(Component)]
 pub struct Inhabitant;
+
+/// Stable serial number so colonists can be told apart in the inspector.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Citizen(pub u32);
+
+/// Id of the `HabitationStructure` the citizen lives in, if any.
+#[derive(Component, Default)]
+pub struct Home(pub Option<String>);
+
+/// Id of the building the citizen works at, if any.
+#[derive(Component, Default)]
+pub struct Workplace(pub Option<String>);
+
+/// How well each need is met, from 0.0 (unmet) to 1.0 (fully met).
+#[derive(Component, Clone, Copy)]
+pub struct Needs {
+    pub food: f32,
+    pub shelter: f32,
+    pub employment: f32,
+    pub services: f32,
+}
+
+impl Default for Needs {
+    fn default() -> Self {
+        Needs { food: 1.0, shelter: 1.0, employment: 1.0, services: 1.0 }
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+pub struct PersonalHappiness(pub f32);
+
+impl Default for PersonalHappiness {
+    fn default() -> Self {
+        PersonalHappiness(50.0)
+    }
+}
@@ -13,10 +13,12 @@ pub const BASE_STORAGE_CAPACITY: f32 = 1000.0;
 pub const STORAGE_SILO_CAPACITY: f32 = 500.0;
 
 use crate::resources::population::PopulationResource;
-use crate::systems::happiness::{happiness_system, calculate_colony_happiness, HappinessResource};
+use crate::resources::spatial::SpatialIndex;
+use crate::systems::happiness::{happiness_system, calculate_colony_happiness, HappinessBreakdown, HappinessResource};
 use crate::systems::services::service_coverage_system;
 use crate::systems::population::population_growth_system;
-use crate::systems::research::research_system;
+use crate::systems::research::{eureka_system, research_system};
+use crate::systems::agents;
 
 // --- Data Structs ---
 
@@ -77,6 +79,7 @@ pub fn construct_legacy_structure(game_state: &mut GameState) {
         return;
     }
     game_state.credits -= initial_tier.construction_credits_cost as f64;
+    game_state.ledger.record(TransactionCategory::Construction, Some(LEGACY_STRUCTURE_ID), -(initial_tier.construction_credits_cost as f64));
     game_state.legacy_structure = Some(LegacyStructure {
         current_tier_index: 0,
         available_tiers: all_tiers.clone(),
@@ -94,6 +97,7 @@ pub fn upgrade_legacy_structure(game_state: &mut GameState) {
                 return;
             }
             game_state.credits -= next_tier.construction_credits_cost as f64;
+            game_state.ledger.record(TransactionCategory::Upgrade, Some(LEGACY_STRUCTURE_ID), -(next_tier.construction_credits_cost as f64));
             structure.current_tier_index = next_tier_index;
             add_notification(&mut game_state.notifications, format!("{} is complete!", next_tier.name), 0.0);
         }
@@ -118,6 +122,7 @@ pub fn construct_administrative_spire(game_state: &mut GameState) {
             return;
         }
         game_state.credits -= initial_tier_def.construction_credits_cost as f64;
+        game_state.ledger.record(TransactionCategory::Construction, Some(ADMINISTRATIVE_SPIRE_ID), -(initial_tier_def.construction_credits_cost as f64));
         add_notification(&mut game_state.notifications, format!("Constructed Administrative Spire."), 0.0);
 
         let spire = AdministrativeSpire {
@@ -154,12 +159,13 @@ pub fn upgrade_administrative_spire(game_state: &mut GameState) {
             return;
         }
 
-        if next_tier_info.nutrient_paste_link_required && game_state.current_resources.get(&ResourceType::NutrientPaste).unwrap_or(&0.0) <= &0.0 {
+        if next_tier_info.nutrient_paste_link_required && game_state.current_resources.get(ResourceType::NutrientPaste) <= 0.0 {
             add_notification(&mut game_state.notifications, "Nutrient Paste link required for Spire upgrade.".to_string(), 0.0);
             return;
         }
 
         game_state.credits -= next_tier_info.upgrade_credits_cost as f64;
+        game_state.ledger.record(TransactionCategory::Upgrade, Some(ADMINISTRATIVE_SPIRE_ID), -(next_tier_info.upgrade_credits_cost as f64));
         spire.current_tier_index = next_tier_index;
         game_state.current_development_phase = next_tier_info.unlocks_phase;
 
@@ -205,6 +211,9 @@ pub struct HabitationStructure {
     pub current_inhabitants: u32,
     pub assigned_specialists: u32,
     pub position: Option<(f32, f32)>,
+    /// Excluded from automatic specialist assignment
+    #[serde(default)]
+    pub manual_lock: bool,
 }
 
 // --- Service Building Data Structures ---
@@ -218,6 +227,14 @@ pub enum ServiceType {
     Spiritual,
 }
 
+pub const ALL_SERVICE_TYPES: [ServiceType; 5] = [
+    ServiceType::Wellness,
+    ServiceType::Security,
+    ServiceType::Education,
+    ServiceType::Recreation,
+    ServiceType::Spiritual,
+];
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ServiceBuildingTier {
     pub name: String,
@@ -238,6 +255,13 @@ pub struct ServiceBuilding {
     pub available_tiers: Vec<ServiceBuildingTier>,
     pub assigned_specialists: u32,
     pub is_active: bool,
+    /// Mothballed buildings pay no upkeep and provide no service
+    #[serde(default)]
+    pub is_mothballed: bool,
+    #[serde(default)]
+    pub status: BuildingStatus,
+    #[serde(default)]
+    pub manual_lock: bool,
     pub position: Option<(f32, f32)>,
 }
 
@@ -268,6 +292,12 @@ pub struct Zone {
     pub available_tiers: Vec<ZoneTier>,
     pub assigned_specialists: u32,
     pub is_active: bool,
+    #[serde(default)]
+    pub is_mothballed: bool,
+    #[serde(default)]
+    pub status: BuildingStatus,
+    #[serde(default)]
+    pub manual_lock: bool,
 }
 
 
@@ -311,11 +341,12 @@ pub fn get_processing_plant_tiers() -> Vec<ProcessingPlantTier> {
     ]
 }
 
-pub fn add_processing_plant(game_state: &mut GameState, tier_index: usize) {
+/// Returns the new plant's ID, or `None` if it could not be built.
+pub fn add_processing_plant(game_state: &mut GameState, tier_index: usize, position: Option<(f32, f32)>) -> Option<String> {
     let all_tiers = get_processing_plant_tiers();
     if tier_index >= all_tiers.len() {
         println!("Error: Invalid tier index for processing plant.");
-        return;
+        return None;
     }
     let tier_info = &all_tiers[tier_index];
     if !game_state.unlocked_techs.contains(&Tech::BasicConstructionProtocols) {
@@ -324,28 +355,35 @@ pub fn add_processing_plant(game_state: &mut GameState, tier_index: usize) {
             "Requires BasicConstructionProtocols to build Processing Plant.".to_string(),
             0.0,
         );
-        return;
+        return None;
     }
     if game_state.credits < tier_info.construction_credits_cost as f64 {
         add_notification(&mut game_state.notifications, format!("Not enough credits to build {}.", tier_info.name), 0.0);
-        return;
+        return None;
     }
+    let new_id = generate_unique_id();
     game_state.credits -= tier_info.construction_credits_cost as f64;
+    game_state.ledger.record(TransactionCategory::Construction, Some(&new_id), -(tier_info.construction_credits_cost as f64));
     add_notification(&mut game_state.notifications, format!("Built {}.", tier_info.name), 0.0);
 
     let new_plant = ProcessingPlantData {
-        id: generate_unique_id(),
+        id: new_id.clone(),
         tier_index,
         available_tiers: all_tiers.clone(),
         assigned_specialists: 0,
         is_active: false,
+        is_mothballed: false,
+        status: BuildingStatus::UnderConstruction,
+        manual_lock: false,
         processing_progress: 0.0,
+        position,
     };
 
     if let Some(unlocked_res) = tier_info.unlocks_resource {
         game_state.unlocked_raw_materials.insert(unlocked_res);
     }
     game_state.processing_plants.push(new_plant);
+    Some(new_id)
 }
 
 pub fn upgrade_processing_plant(game_state: &mut GameState, plant_id: &str) {
@@ -361,6 +399,7 @@ pub fn upgrade_processing_plant(game_state: &mut GameState, plant_id: &str) {
                 return;
             }
             game_state.credits -= upgrade_cost as f64;
+            game_state.ledger.record(TransactionCategory::Upgrade, Some(plant_id), -(upgrade_cost as f64));
             println!("Upgraded Processing Plant {} to {} for {} credits. Remaining credits: {:.2}", plant_id, next_tier_info.name, upgrade_cost, game_state.credits);
 
             plant.tier_index = next_tier_index;
@@ -399,7 +438,7 @@ pub fn remove_processing_plant(game_state: &mut GameState, plant_id: &str) {
 pub fn assign_specialists_to_processing_plant(game_state: &mut GameState, plant_id: &str, num_to_assign: u32) {
     if let Some(plant) = game_state.processing_plants.iter_mut().find(|p| p.id == plant_id) {
         if let Some(tier) = plant.available_tiers.get(plant.tier_index) {
-            let available_general_inhabitants = game_state.total_inhabitants.saturating_sub(game_state.assigned_specialists_total);
+            let available_general_inhabitants = game_state.demographics.working_age.saturating_sub(game_state.assigned_specialists_total);
             if available_general_inhabitants < num_to_assign {
                 println!("Not enough unassigned inhabitants for Processing Plant {}.", plant_id); return;
             }
@@ -442,34 +481,52 @@ pub fn unassign_specialists_from_processing_plant(game_state: &mut GameState, pl
 }
 
 pub fn processing_plant_operations_system(game_state: &mut GameState, time_delta_secs: f32) {
+    let capacity = BASE_STORAGE_CAPACITY + game_state.storage_silos.len() as f32 * STORAGE_SILO_CAPACITY;
     for plant in game_state.processing_plants.iter_mut() {
         if let Some(tier) = plant.available_tiers.get(plant.tier_index) {
-            let has_power = *game_state.current_resources.get(&ResourceType::Power).unwrap_or(&0.0) >= tier.power_requirement as f32;
-            let has_specialists = plant.assigned_specialists >= tier.specialist_requirement;
-
-            plant.is_active = has_power && has_specialists;
+            let has_power = game_state.current_resources.get(ResourceType::Power) >= tier.power_requirement as f32;
+            let staffing = staffing_fraction(plant.assigned_specialists, tier.specialist_requirement);
+            let has_specialists = staffing > 0.0;
+            let has_inputs = game_state.current_resources.can_afford(&tier.batch_inputs(1.0));
+            let output_full = tier
+                .output_resource
+                .is_some_and(|(resource, _)| game_state.current_resources.get(resource) >= capacity);
+
+            plant.is_active = has_power && has_specialists && !plant.is_mothballed;
+            plant.status = if plant.is_mothballed {
+                BuildingStatus::Mothballed
+            } else if !has_specialists {
+                BuildingStatus::Understaffed
+            } else if !has_power {
+                BuildingStatus::Unpowered
+            } else if !has_inputs {
+                BuildingStatus::MissingInputs
+            } else if output_full {
+                BuildingStatus::OutputStorageFull
+            } else {
+                BuildingStatus::Operational
+            };
 
             if !plant.is_active {
                 plant.processing_progress = 0.0;
                 continue;
             }
+            if output_full {
+                continue;
+            }
 
-            if let (Some((input_type, input_amount_per_batch)), Some((output_type, output_amount_per_batch)), Some(rate)) =
-                (tier.input_resource, tier.output_resource, tier.processing_rate_per_sec) {
-
-                let potential_batches_this_tick = rate * time_delta_secs;
+            if let (Some(_), Some(_), Some(rate)) = (tier.input_resource, tier.output_resource, tier.processing_rate_per_sec) {
+                let rate = game_state.modifiers.apply(STAT_PROCESSING_THROUGHPUT, rate);
+                let potential_batches_this_tick = rate * time_delta_secs * staffing;
                 plant.processing_progress += potential_batches_this_tick;
 
                 if plant.processing_progress >= 1.0 {
                     let num_batches_to_process = plant.processing_progress.floor();
-                    let total_input_needed = input_amount_per_batch as f32 * num_batches_to_process;
-                    let current_input_available = *game_state.current_resources.get(&input_type).unwrap_or(&0.0);
-
-                    if current_input_available >= total_input_needed {
-                        *game_state.current_resources.entry(input_type).or_insert(0.0) -= total_input_needed;
-                        let total_output_produced = output_amount_per_batch as f32 * num_batches_to_process;
-                        *game_state.current_resources.entry(output_type).or_insert(0.0) += total_output_produced;
+                    let accounts = &mut game_state.resource_accounts;
+                    let stock = &mut game_state.current_resources;
 
+                    if accounts.withdraw(stock, &tier.batch_inputs(num_batches_to_process), &plant.id).is_ok() {
+                        accounts.deposit(stock, &tier.batch_outputs(num_batches_to_process), capacity, &plant.id);
                         plant.processing_progress -= num_batches_to_process;
                     }
                 }
@@ -502,6 +559,7 @@ pub enum BuildingType {
     ResearchInstitute,
     Fabricator,
     ProcessingPlant,
+    Spaceport,
 }
 
 pub const ALL_BUILDING_TYPES: &[BuildingType] = &[
@@ -512,6 +570,7 @@ pub const ALL_BUILDING_TYPES: &[BuildingType] = &[
     BuildingType::ResearchInstitute,
     BuildingType::Fabricator,
     BuildingType::ProcessingPlant,
+    BuildingType::Spaceport,
 ];
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -538,796 +597,2231 @@ impl fmt::Display for Tech {
     }
 }
 
-// --- Data-Driven Building Structs ---
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct ExtractorData {
-    pub id: String,
-    pub is_staffed: bool,
+pub const ALL_TECHS: [Tech; 6] = [
+    Tech::BasicConstructionProtocols,
+    Tech::EfficientExtraction,
+    Tech::AdvancedFabrication,
+    Tech::IndustrialProcessing,
+    Tech::ZoningOrdinances,
+    Tech::ArcologyConstruction,
+];
+
+/// Something a tech does once researched.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TechEffect {
+    /// A standing modifier on a named stat
+    Modifier { stat: &'static str, op: ModifierOp },
+    /// A building or building tier that can now be constructed
+    Unlocks(String),
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct BioDomeData {
-    pub id: String,
-    pub is_staffed: bool,
+impl TechEffect {
+    pub fn describe(&self) -> String {
+        match self {
+            TechEffect::Modifier { stat, op: ModifierOp::Multiply(factor) } => {
+                format!("{:+.0}% {}", (factor - 1.0) * 100.0, stat_label(stat))
+            }
+            TechEffect::Modifier { stat, op: ModifierOp::Add(value) } => format!("{:+} {}", value, stat_label(stat)),
+            TechEffect::Unlocks(name) => format!("Unlocks {}", name),
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct PowerRelayData {
-    pub id: String,
+/// Content data for what each tech grants. Tier unlocks come from each tier's `required_tech`
+/// so the tier tables stay the one place that gates construction.
+pub fn tech_effects(tech: Tech) -> Vec<TechEffect> {
+    let mut effects = match tech {
+        Tech::BasicConstructionProtocols => ["Storage Silo", "Research Institute", "Fabricator", "Processing Plant", "Spaceport"]
+            .into_iter()
+            .map(|name| TechEffect::Unlocks(name.to_string()))
+            .collect(),
+        Tech::EfficientExtraction => vec![TechEffect::Modifier { stat: STAT_EXTRACTOR_OUTPUT, op: ModifierOp::Multiply(1.25) }],
+        Tech::IndustrialProcessing => vec![TechEffect::Modifier { stat: STAT_PROCESSING_THROUGHPUT, op: ModifierOp::Multiply(1.5) }],
+        Tech::AdvancedFabrication | Tech::ZoningOrdinances | Tech::ArcologyConstruction => Vec::new(),
+    };
+
+    let unlocked_tiers = get_fabricator_tiers()
+        .into_iter()
+        .filter(|t| t.required_tech == Some(tech))
+        .map(|t| t.name)
+        .chain(get_habitation_tiers().into_iter().filter(|t| t.required_tech == Some(tech)).map(|t| t.name))
+        .chain(
+            [ZoneType::Commercial, ZoneType::LightIndustry]
+                .into_iter()
+                .flat_map(get_zone_tiers)
+                .filter(|t| t.required_tech == Some(tech))
+                .map(|t| t.name),
+        )
+        .chain(
+            ALL_SERVICE_TYPES
+                .into_iter()
+                .flat_map(get_service_building_tiers)
+                .filter(|t| t.required_tech == Some(tech))
+                .map(|t| t.name),
+        );
+    effects.extend(unlocked_tiers.map(TechEffect::Unlocks));
+    effects
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct ResearchInstituteData {
-    pub id: String,
-    pub is_staffed: bool,
+// --- Tech Tree ---
+
+/// Discipline a research institute works in. Each field earns its own points, so projects
+/// drawing on different fields progress side by side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ResearchField {
+    #[default]
+    Engineering,
+    Biology,
+    Social,
+    Physics,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct StorageSiloData {
-    pub id: String,
+pub const ALL_RESEARCH_FIELDS: [ResearchField; 4] =
+    [ResearchField::Engineering, ResearchField::Biology, ResearchField::Social, ResearchField::Physics];
+
+impl ResearchField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ResearchField::Engineering => "Engineering",
+            ResearchField::Biology => "Biology",
+            ResearchField::Social => "Social",
+            ResearchField::Physics => "Physics",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ResearchField::Engineering => ResearchField::Biology,
+            ResearchField::Biology => ResearchField::Social,
+            ResearchField::Social => ResearchField::Physics,
+            ResearchField::Physics => ResearchField::Engineering,
+        }
+    }
 }
 
-#[derive(Resource, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct GameState {
-    pub administrative_spire: Option<AdministrativeSpire>,
-    pub legacy_structure: Option<LegacyStructure>,
-    pub current_development_phase: DevelopmentPhase,
-    pub current_resources: HashMap<ResourceType, f32>,
-    pub building_costs: HashMap<BuildingType, HashMap<ResourceType, f32>>,
-    pub unlocked_techs: HashSet<Tech>,
-    pub research_progress: Option<(Tech, f32)>,
-    pub tech_costs: HashMap<Tech, u32>,
-    pub habitation_structures: Vec<HabitationStructure>,
-    pub total_inhabitants: u32,
-    /// Accumulator for fractional population growth between ticks
-    #[serde(default)]
-    pub population_growth_progress: f32,
-    pub assigned_workforce: u32,
-    pub available_housing_capacity: u32,
-    pub total_specialist_slots: u32,
-    pub assigned_specialists_total: u32,
-    pub service_buildings: Vec<ServiceBuilding>,
-    pub zones: Vec<Zone>,
-    pub civic_index: u32,
-    pub colony_happiness: f32,
-    pub simulated_has_sufficient_nutrient_paste: bool,
-    pub fabricators: Vec<FabricatorData>,
-    pub processing_plants: Vec<ProcessingPlantData>,
-    pub unlocked_raw_materials: HashSet<ResourceType>,
-    pub credits: f64,
-    pub total_generated_power: f32,
-    pub total_consumed_power: f32,
-    pub notifications: VecDeque<NotificationEvent>,
-    
-    // New data-driven building lists
-    pub extractors: Vec<ExtractorData>,
-    pub bio_domes: Vec<BioDomeData>,
-    pub power_relays: Vec<PowerRelayData>,
-    pub research_institutes: Vec<ResearchInstituteData>,
-    pub storage_silos: Vec<StorageSiloData>,
+/// Where a tech sits in the tree: what must be researched first, how developed the colony must be
+/// and the points each field has to contribute.
+pub struct TechNode {
+    pub prerequisites: &'static [Tech],
+    pub required_phase: DevelopmentPhase,
+    pub research_costs: &'static [(ResearchField, u32)],
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct NotificationEvent {
-    pub message: String,
-    pub timestamp: f64,
+pub fn tech_node(tech: Tech) -> TechNode {
+    match tech {
+        Tech::BasicConstructionProtocols => TechNode {
+            prerequisites: &[],
+            required_phase: DevelopmentPhase::DP1,
+            research_costs: &[(ResearchField::Engineering, 100)],
+        },
+        Tech::EfficientExtraction => TechNode {
+            prerequisites: &[Tech::BasicConstructionProtocols],
+            required_phase: DevelopmentPhase::DP1,
+            research_costs: &[(ResearchField::Engineering, 150), (ResearchField::Physics, 100)],
+        },
+        Tech::ZoningOrdinances => TechNode {
+            prerequisites: &[Tech::BasicConstructionProtocols],
+            required_phase: DevelopmentPhase::DP1,
+            research_costs: &[(ResearchField::Social, 300), (ResearchField::Engineering, 100)],
+        },
+        Tech::AdvancedFabrication => TechNode {
+            prerequisites: &[Tech::BasicConstructionProtocols],
+            required_phase: DevelopmentPhase::DP2,
+            research_costs: &[(ResearchField::Engineering, 300), (ResearchField::Physics, 200)],
+        },
+        Tech::IndustrialProcessing => TechNode {
+            prerequisites: &[Tech::EfficientExtraction],
+            required_phase: DevelopmentPhase::DP2,
+            research_costs: &[(ResearchField::Engineering, 250), (ResearchField::Biology, 250)],
+        },
+        Tech::ArcologyConstruction => TechNode {
+            prerequisites: &[Tech::AdvancedFabrication, Tech::ZoningOrdinances],
+            required_phase: DevelopmentPhase::DP3,
+            research_costs: &[
+                (ResearchField::Engineering, 400),
+                (ResearchField::Social, 400),
+                (ResearchField::Biology, 200),
+            ],
+        },
+    }
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct FabricatorTier {
-    pub name: String,
-    pub input_resources: HashMap<ResourceType, u32>,
-    pub output_product: ResourceType,
-    pub output_quantity: u32,
-    pub production_time_secs: f32,
-    pub power_requirement: u32,
-    pub specialist_requirement: u32,
-    pub construction_credits_cost: u32,
-    pub upkeep_cost: u32,
+/// Optional milestone that pays part of a tech's research cost in every field once the colony reaches it.
+pub struct EurekaBoost {
+    pub description: &'static str,
+    pub condition: fn(&GameState) -> bool,
+    pub share: f32,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
-pub struct FabricatorData {
-    pub id: String,
-    pub tier_index: usize,
-    pub available_tiers: Vec<FabricatorTier>,
-    pub assigned_specialists: u32,
-    pub is_active: bool,
-    pub production_progress_secs: f32,
+pub fn tech_eureka(tech: Tech) -> Option<EurekaBoost> {
+    let boost = match tech {
+        Tech::BasicConstructionProtocols => EurekaBoost {
+            description: "Construct the Administrative Spire",
+            condition: |gs| gs.administrative_spire.is_some(),
+            share: 0.5,
+        },
+        Tech::EfficientExtraction => EurekaBoost {
+            description: "Build 3 Extractors",
+            condition: |gs| gs.extractors.len() >= 3,
+            share: 0.25,
+        },
+        Tech::ZoningOrdinances => EurekaBoost {
+            description: "Reach 100 inhabitants",
+            condition: |gs| gs.total_inhabitants >= 100,
+            share: 0.25,
+        },
+        Tech::AdvancedFabrication => EurekaBoost {
+            description: "Build 2 Fabricators",
+            condition: |gs| gs.fabricators.len() >= 2,
+            share: 0.25,
+        },
+        Tech::IndustrialProcessing => EurekaBoost {
+            description: "Build 3 Processing Plants",
+            condition: |gs| gs.processing_plants.len() >= 3,
+            share: 0.25,
+        },
+        Tech::ArcologyConstruction => EurekaBoost {
+            description: "Run a Commercial and a Light Industry zone",
+            condition: |gs| {
+                [ZoneType::Commercial, ZoneType::LightIndustry]
+                    .iter()
+                    .all(|t| gs.zones.iter().any(|z| z.zone_type == *t && z.is_active))
+            },
+            share: 0.25,
+        },
+    };
+    Some(boost)
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct ProcessingPlantTier {
-    pub name: String,
-    pub unlocks_resource: Option<ResourceType>,
-    pub input_resource: Option<(ResourceType, u32)>,
-    pub output_resource: Option<(ResourceType, u32)>,
-    pub processing_rate_per_sec: Option<f32>,
-    pub power_requirement: u32,
-    pub specialist_requirement: u32,
-    pub construction_credits_cost: u32,
-    pub upkeep_cost: u32,
+fn is_research_scheduled(game_state: &GameState, tech: Tech) -> bool {
+    game_state.research_queue.contains(&tech)
 }
 
-#[derive(Serialize, Deserialize, Clone)]
-pub struct ProcessingPlantData {
-    pub id: String,
-    pub tier_index: usize,
-    pub available_tiers: Vec<ProcessingPlantTier>,
-    pub assigned_specialists: u32,
-    pub is_active: bool,
-    pub processing_progress: f32,
+/// True for queued techs whose prerequisites are all researched; only these receive points.
+pub fn is_research_active(game_state: &GameState, tech: Tech) -> bool {
+    game_state.research_queue.contains(&tech)
+        && tech_node(tech).prerequisites.iter().all(|p| game_state.unlocked_techs.contains(p))
 }
 
-#[derive(Resource, Default, Clone, Copy, Serialize, Deserialize)]
-pub struct ColonyStats {
-    pub total_housing: u32,
-    pub total_jobs: u32,
-    pub happiness: f32,
-    pub credits: f64,
-    pub net_power: f32,
-    pub nutrient_paste: f32,
+fn research_field_cost(tech: Tech, field: ResearchField) -> u32 {
+    tech_node(tech).research_costs.iter().find(|(f, _)| *f == field).map_or(0, |(_, c)| *c)
 }
 
+/// Points `field` has put into `tech` so far, including any eureka boost claimed for it.
+pub fn research_field_progress(game_state: &GameState, tech: Tech, field: ResearchField) -> f32 {
+    let cost = research_field_cost(tech, field) as f32;
+    let invested = game_state.research_progress.get(&tech).and_then(|p| p.get(&field)).copied().unwrap_or(0.0);
+    let boost = match tech_eureka(tech) {
+        Some(eureka) if game_state.eureka_claimed.contains(&tech) => cost * eureka.share,
+        _ => 0.0,
+    };
+    (invested + boost).min(cost)
+}
 
+/// Points `field` still owes `tech`.
+pub fn research_remaining(game_state: &GameState, tech: Tech, field: ResearchField) -> f32 {
+    (research_field_cost(tech, field) as f32 - research_field_progress(game_state, tech, field)).max(0.0)
+}
 
-#[derive(Resource, Serialize, Deserialize, Clone)]
-pub struct ServiceCoverage {
-    pub coverage: HashMap<ServiceType, f32>,
+/// Switches an institute to the next research field.
+pub fn cycle_institute_field(game_state: &mut GameState, institute_id: &str) {
+    if let Some(institute) = game_state.research_institutes.iter_mut().find(|ri| ri.id == institute_id) {
+        institute.field = institute.field.next();
+    } else {
+        println!("Research Institute with ID {} not found.", institute_id);
+    }
 }
 
-impl Default for ServiceCoverage {
-    fn default() -> Self {
-        ServiceCoverage { coverage: HashMap::new() }
+/// Why `tech` can't be queued, or `None` if it can. Prerequisites already queued count as met,
+/// since the queue researches them first.
+pub fn tech_lock_reason(game_state: &GameState, tech: Tech) -> Option<String> {
+    if game_state.unlocked_techs.contains(&tech) {
+        return Some("Already researched".to_string());
+    }
+    if is_research_scheduled(game_state, tech) {
+        return Some("Already queued".to_string());
+    }
+    let node = tech_node(tech);
+    if game_state.current_development_phase < node.required_phase {
+        return Some(format!("Requires Development Phase {:?}", node.required_phase));
     }
+    let missing: Vec<String> = node
+        .prerequisites
+        .iter()
+        .filter(|p| !game_state.unlocked_techs.contains(p) && !is_research_scheduled(game_state, **p))
+        .map(|p| p.to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Some(format!("Requires {}", missing.join(", ")));
+    }
+    None
 }
 
-#[derive(Resource, Default, Serialize, Deserialize, Clone)]
-pub struct GraphData {
-    pub history: VecDeque<ColonyStats>,
+/// Pays for `tech` and adds it to the end of the research queue.
+pub fn queue_research(game_state: &mut GameState, tech: Tech) {
+    if let Some(reason) = tech_lock_reason(game_state, tech) {
+        add_notification(&mut game_state.notifications, format!("Cannot queue {}: {}.", tech, reason), 0.0);
+        return;
+    }
+    let cost = *game_state.tech_costs.get(&tech).unwrap_or(&0) as f64;
+    if game_state.credits < cost {
+        add_notification(&mut game_state.notifications, format!("Not enough credits to research {}.", tech), 0.0);
+        return;
+    }
+    game_state.credits -= cost;
+    game_state.ledger.record(TransactionCategory::Research, None, -cost);
+    game_state.research_queue.push(tech);
 }
 
-impl Default for GameState {
-    fn default() -> Self {
-        let mut building_costs = HashMap::new();
-        building_costs.insert(BuildingType::Extractor, HashMap::from([(ResourceType::FerrocreteOre, 75.0)]));
-        building_costs.insert(BuildingType::BioDome, HashMap::from([(ResourceType::FerrocreteOre, 50.0)]));
-        building_costs.insert(BuildingType::PowerRelay, HashMap::from([(ResourceType::FerrocreteOre, 60.0)]));
-        building_costs.insert(BuildingType::StorageSilo, HashMap::from([(ResourceType::FerrocreteOre, 100.0)]));
-        building_costs.insert(BuildingType::ResearchInstitute, HashMap::from([(ResourceType::FerrocreteOre, 150.0)]));
-        building_costs.insert(BuildingType::Fabricator, HashMap::from([(ResourceType::FerrocreteOre, 200.0)]));
-        building_costs.insert(BuildingType::ProcessingPlant, HashMap::from([(ResourceType::FerrocreteOre, 180.0)]));
+/// Drops `tech` from the queue along with anything queued that depends on it, refunding each.
+/// Points already put into them are lost.
+pub fn dequeue_research(game_state: &mut GameState, tech: Tech) {
+    let mut removed = vec![tech];
+    let mut index = 0;
+    while index < removed.len() {
+        let dropped = removed[index];
+        removed.extend(
+            game_state
+                .research_queue
+                .iter()
+                .filter(|t| tech_node(**t).prerequisites.contains(&dropped) && !removed.contains(t))
+                .copied()
+                .collect::<Vec<_>>(),
+        );
+        index += 1;
+    }
 
-        let mut tech_costs = HashMap::new();
-        tech_costs.insert(Tech::BasicConstructionProtocols, 100);
-        tech_costs.insert(Tech::EfficientExtraction, 250);
-        tech_costs.insert(Tech::AdvancedFabrication, 500);
-        tech_costs.insert(Tech::IndustrialProcessing, 500);
-        tech_costs.insert(Tech::ZoningOrdinances, 400);
-        tech_costs.insert(Tech::ArcologyConstruction, 1000);
+    for dropped in removed {
+        if let Some(position) = game_state.research_queue.iter().position(|t| *t == dropped) {
+            game_state.research_queue.remove(position);
+            game_state.research_progress.remove(&dropped);
+            let refund = *game_state.tech_costs.get(&dropped).unwrap_or(&0) as f64;
+            game_state.credits += refund;
+            game_state.ledger.record(TransactionCategory::Research, None, refund);
+            add_notification(&mut game_state.notifications, format!("{} removed from the research queue.", dropped), 0.0);
+        }
+    }
+}
 
+/// Moves `tech` one place earlier or later in the queue, as long as prerequisites stay ahead of the techs needing them.
+pub fn move_research_in_queue(game_state: &mut GameState, tech: Tech, earlier: bool) {
+    let Some(index) = game_state.research_queue.iter().position(|t| *t == tech) else {
+        return;
+    };
+    let other = if earlier { index.checked_sub(1) } else { Some(index + 1) };
+    let Some(other) = other.filter(|o| *o < game_state.research_queue.len()) else {
+        return;
+    };
+    let (first, second) = (game_state.research_queue[index.min(other)], game_state.research_queue[index.max(other)]);
+    if tech_node(second).prerequisites.contains(&first) {
+        add_notification(
+            &mut game_state.notifications,
+            format!("{} must be researched before {}.", first, second),
+            0.0,
+        );
+        return;
+    }
+    game_state.research_queue.swap(index, other);
+}
 
-        let mut current_resources = HashMap::new();
-        current_resources.insert(ResourceType::NutrientPaste, 50.0);
-        current_resources.insert(ResourceType::FerrocreteOre, 200.0);
-        current_resources.insert(ResourceType::CuprumDeposits, 50.0);
-        current_resources.insert(ResourceType::Power, 100.0);
-        current_resources.insert(ResourceType::ManufacturedGoods, 0.0);
-        current_resources.insert(ResourceType::AdvancedComponents, 0.0);
-        current_resources.insert(ResourceType::RefinedXylos, 0.0);
-        current_resources.insert(ResourceType::ProcessedQuantium, 0.0);
-        current_resources.insert(ResourceType::RawXylos, 0.0);
-        current_resources.insert(ResourceType::RawQuantium, 0.0);
+// --- Staffing ---
 
-        let mut new_state = Self {
-            administrative_spire: None,
-            legacy_structure: None,
-            current_development_phase: DevelopmentPhase::default(),
-            current_resources,
-            building_costs,
-            unlocked_techs: HashSet::new(),
-            research_progress: None,
-            tech_costs,
-            habitation_structures: Vec::new(),
-            total_inhabitants: 5,
-            population_growth_progress: 0.0,
-            assigned_workforce: 0,
-            available_housing_capacity: 0,
-            total_specialist_slots: 0,
-            assigned_specialists_total: 0,
-            service_buildings: Vec::new(),
-            zones: Vec::new(),
-            civic_index: 0,
-            colony_happiness: 50.0,
-            simulated_has_sufficient_nutrient_paste: true,
-            fabricators: Vec::new(),
-            processing_plants: Vec::new(),
-            unlocked_raw_materials: HashSet::new(),
-            credits: 10000.0,
-            total_generated_power: 0.0,
-            total_consumed_power: 0.0,
-            notifications: VecDeque::new(),
-            extractors: Vec::new(),
-            bio_domes: Vec::new(),
-            power_relays: Vec::new(),
-            research_institutes: Vec::new(),
-            storage_silos: Vec::new(),
-        };
+pub const EXTRACTOR_CREW: u32 = 5;
+pub const BIO_DOME_CREW: u32 = 10;
+pub const RESEARCH_INSTITUTE_CREW: u32 = 15;
+/// Researcher posts at each institute, filled by specialists.
+pub const RESEARCH_INSTITUTE_SPECIALISTS: u32 = 4;
+/// Share of a full crew below which a building idles instead of running at reduced output.
+pub const MINIMUM_CREW_FRACTION: f32 = 0.5;
 
-        Self::add_notification_internal(&mut new_state.notifications, "Colony established. Welcome, Commander!".to_string(), 0.0);
-        Self::add_notification_internal(&mut new_state.notifications, "Low power levels detected early in the simulation.".to_string(), 0.1);
-        Self::add_notification_internal(&mut new_state.notifications, "A strange signal was briefly detected from the nearby asteroid belt.".to_string(), 0.2);
+pub fn minimum_crew(full_crew: u32) -> u32 {
+    (full_crew as f32 * MINIMUM_CREW_FRACTION).ceil() as u32
+}
 
-        new_state
+/// Share of full output a building achieves with `assigned` of `full_crew` workers.
+/// Zero below the minimum crew.
+pub fn staffing_fraction(assigned: u32, full_crew: u32) -> f32 {
+    if full_crew == 0 {
+        return 1.0;
     }
+    if assigned < minimum_crew(full_crew) {
+        return 0.0;
+    }
+    (assigned as f32 / full_crew as f32).min(1.0)
 }
 
-impl GameState {
-    fn add_notification_internal(notifications_vec: &mut VecDeque<NotificationEvent>, message: String, timestamp: f64) {
-        notifications_vec.push_front(NotificationEvent {
-            message,
-            timestamp,
-        });
-        if notifications_vec.len() > 20 {
-            notifications_vec.pop_back();
+// --- Workforce Allocation ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WorkforceCategory {
+    Extractors,
+    BioDomes,
+    ResearchInstitutes,
+}
+
+impl WorkforceCategory {
+    pub fn label(&self) -> &'static str {
+        match self {
+            WorkforceCategory::Extractors => "Extractors",
+            WorkforceCategory::BioDomes => "Bio-Domes",
+            WorkforceCategory::ResearchInstitutes => "Research Institutes",
         }
     }
-}
 
-pub fn add_notification(notifications: &mut VecDeque<NotificationEvent>, message: String, current_time_seconds: f64) {
-    GameState::add_notification_internal(notifications, message, current_time_seconds);
-}
+    pub fn full_crew(&self) -> u32 {
+        match self {
+            WorkforceCategory::Extractors => EXTRACTOR_CREW,
+            WorkforceCategory::BioDomes => BIO_DOME_CREW,
+            WorkforceCategory::ResearchInstitutes => RESEARCH_INSTITUTE_CREW,
+        }
+    }
 
-pub fn get_fabricator_tiers() -> Vec<FabricatorTier> {
+    /// The stockpile this category keeps from running out, if any.
+    pub fn produced_resource(&self) -> Option<ResourceType> {
+        match self {
+            WorkforceCategory::Extractors => Some(ResourceType::FerrocreteOre),
+            WorkforceCategory::BioDomes => Some(ResourceType::NutrientPaste),
+            WorkforceCategory::ResearchInstitutes => None,
+        }
+    }
+}
+
+pub fn default_workforce_priority() -> Vec<WorkforceCategory> {
     vec![
-        FabricatorTier {
-            name: "Basic Fabricator".to_string(),
-            input_resources: HashMap::from([
-                (ResourceType::FerrocreteOre, 2),
-                (ResourceType::CuprumDeposits, 1),
-            ]),
-            output_product: ResourceType::ManufacturedGoods,
-            output_quantity: 1,
-            production_time_secs: 10.0,
-            power_requirement: 15,
-            specialist_requirement: 1,
-            construction_credits_cost: 200,
-            upkeep_cost: 10,
-        },
-        FabricatorTier {
-            name: "Advanced Fabricator".to_string(),
-            input_resources: HashMap::from([
-                (ResourceType::ManufacturedGoods, 2),
-                (ResourceType::RefinedXylos, 1),
-            ]),
-            output_product: ResourceType::AdvancedComponents,
-            output_quantity: 1,
-            production_time_secs: 20.0,
-            power_requirement: 30,
-            specialist_requirement: 2,
-            construction_credits_cost: 500,
-            upkeep_cost: 25,
-        },
+        WorkforceCategory::Extractors,
+        WorkforceCategory::BioDomes,
+        WorkforceCategory::ResearchInstitutes,
     ]
 }
 
-fn check_fabricator_inputs(
-    current_resources: &HashMap<ResourceType, f32>,
-    fabricator_tier: &FabricatorTier
-) -> bool {
-    for (resource_type, required_amount) in &fabricator_tier.input_resources {
-        if current_resources.get(resource_type).unwrap_or(&0.0) < &(*required_amount as f32) {
-            return false;
+/// Per-building override on top of the category order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub enum WorkPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl WorkPriority {
+    pub fn next(&self) -> Self {
+        match self {
+            WorkPriority::Low => WorkPriority::Normal,
+            WorkPriority::Normal => WorkPriority::High,
+            WorkPriority::High => WorkPriority::Low,
         }
     }
-    true
 }
 
-
-pub fn add_fabricator(game_state: &mut GameState, tier_index: usize) {
-    let all_tiers = get_fabricator_tiers();
-    if tier_index >= all_tiers.len() {
-        println!("Error: Invalid tier index for fabricator.");
-        return;
-    }
-    let tier_info = &all_tiers[tier_index];
-    if !game_state.unlocked_techs.contains(&Tech::BasicConstructionProtocols) {
-        add_notification(
-            &mut game_state.notifications,
-            "Requires BasicConstructionProtocols to build Fabricator.".to_string(),
-            0.0,
-        );
-        return;
-    }
-    if game_state.credits < tier_info.construction_credits_cost as f64 {
-        add_notification(&mut game_state.notifications, format!("Not enough credits to build {}.", tier_info.name), 0.0);
+pub fn move_workforce_priority(game_state: &mut GameState, category: WorkforceCategory, raise: bool) {
+    let Some(index) = game_state.workforce_priority.iter().position(|c| *c == category) else {
         return;
+    };
+    if raise && index > 0 {
+        game_state.workforce_priority.swap(index, index - 1);
+    } else if !raise && index + 1 < game_state.workforce_priority.len() {
+        game_state.workforce_priority.swap(index, index + 1);
     }
-    game_state.credits -= tier_info.construction_credits_cost as f64;
-    add_notification(&mut game_state.notifications, format!("Built {}.", tier_info.name), 0.0);
+}
 
-    let new_fabricator = FabricatorData {
-        id: generate_unique_id(),
-        tier_index,
-        available_tiers: all_tiers.clone(),
-        assigned_specialists: 0,
-        is_active: false,
-        production_progress_secs: 0.0,
+pub fn toggle_workforce_auto_balance(game_state: &mut GameState) {
+    game_state.workforce_auto_balance = !game_state.workforce_auto_balance;
+    let message = if game_state.workforce_auto_balance {
+        "Workforce auto-balance enabled: workers follow the scarcest resource."
+    } else {
+        "Workforce auto-balance disabled."
     };
-    game_state.fabricators.push(new_fabricator);
+    add_notification(&mut game_state.notifications, message.to_string(), 0.0);
 }
 
-pub fn upgrade_fabricator(game_state: &mut GameState, fabricator_id: &str) {
-    if let Some(fab) = game_state.fabricators.iter_mut().find(|f| f.id == fabricator_id) {
-        if fab.tier_index < fab.available_tiers.len() - 1 {
-            let next_tier_index = fab.tier_index + 1;
-            let next_tier_info = &fab.available_tiers[next_tier_index];
-            let upgrade_cost = next_tier_info.construction_credits_cost;
+pub fn cycle_building_work_priority(game_state: &mut GameState, building_id: &str) {
+    if let Some(extractor) = game_state.extractors.iter_mut().find(|b| b.id == building_id) {
+        extractor.work_priority = extractor.work_priority.next();
+    } else if let Some(dome) = game_state.bio_domes.iter_mut().find(|b| b.id == building_id) {
+        dome.work_priority = dome.work_priority.next();
+    } else if let Some(institute) = game_state.research_institutes.iter_mut().find(|b| b.id == building_id) {
+        institute.work_priority = institute.work_priority.next();
+    } else {
+        println!("Building with ID {} not found for work priority.", building_id);
+    }
+}
 
-            if game_state.credits < upgrade_cost as f64 {
-                println!("Not enough credits to upgrade Fabricator {} to {}. Required: {}, Available: {:.2}", fabricator_id, next_tier_info.name, upgrade_cost, game_state.credits);
-                return;
-            }
-            game_state.credits -= upgrade_cost as f64;
-            println!("Upgraded Fabricator {} to {} for {} credits. Remaining credits: {:.2}", fabricator_id, next_tier_info.name, upgrade_cost, game_state.credits);
+/// Category order used this tick. With auto-balance on, categories whose stockpile
+/// runs out soonest move to the front; ties keep the player's order.
+pub fn effective_workforce_priority(game_state: &GameState) -> Vec<WorkforceCategory> {
+    let mut order = game_state.workforce_priority.clone();
+    if game_state.workforce_auto_balance {
+        let urgency = |category: &WorkforceCategory| {
+            category
+                .produced_resource()
+                .and_then(|resource| {
+                    game_state
+                        .resource_accounts
+                        .ticks_to_depletion(resource, game_state.current_resources.get(resource))
+                })
+                .unwrap_or(f32::INFINITY)
+        };
+        order.sort_by(|a, b| urgency(a).partial_cmp(&urgency(b)).unwrap_or(std::cmp::Ordering::Equal));
+    }
+    order
+}
 
-            fab.tier_index = next_tier_index;
-            fab.production_progress_secs = 0.0;
+// --- Specialist Assignment ---
 
-            if fab.assigned_specialists > next_tier_info.specialist_requirement {
-                let to_unassign = fab.assigned_specialists - next_tier_info.specialist_requirement;
-                fab.assigned_specialists -= to_unassign;
-                game_state.assigned_specialists_total -= to_unassign;
-            }
-            println!("Upgraded Fabricator {} to {}", fabricator_id, next_tier_info.name);
-        } else {
-            println!("Fabricator {} is already at max tier.", fabricator_id);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SpecialistCategory {
+    ServiceBuildings,
+    Fabricators,
+    ProcessingPlants,
+    Zones,
+    Habitation,
+    Research,
+}
+
+impl SpecialistCategory {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SpecialistCategory::ServiceBuildings => "Service Buildings",
+            SpecialistCategory::Fabricators => "Fabricators",
+            SpecialistCategory::ProcessingPlants => "Processing Plants",
+            SpecialistCategory::Zones => "Zones",
+            SpecialistCategory::Habitation => "Habitation",
+            SpecialistCategory::Research => "Research Institutes",
+        }
+    }
+
+    /// What a specialist trained for this category is called.
+    pub fn profession(&self) -> &'static str {
+        match self {
+            SpecialistCategory::ServiceBuildings => "Service Staff",
+            SpecialistCategory::Fabricators => "Fabrication Technician",
+            SpecialistCategory::ProcessingPlants => "Plant Operator",
+            SpecialistCategory::Zones => "Zone Administrator",
+            SpecialistCategory::Habitation => "Habitat Manager",
+            SpecialistCategory::Research => "Researcher",
         }
-    } else {
-        println!("Fabricator with ID {} not found.", fabricator_id);
     }
 }
 
-pub fn remove_fabricator(game_state: &mut GameState, fabricator_id: &str) {
-    if let Some(index) = game_state.fabricators.iter().position(|f| f.id == fabricator_id) {
-        let removed_fab = game_state.fabricators.remove(index);
-        game_state.assigned_specialists_total = game_state.assigned_specialists_total.saturating_sub(removed_fab.assigned_specialists);
-        println!("Removed Fabricator with ID {}", fabricator_id);
-    } else {
-        println!("Fabricator with ID {} not found for removal.", fabricator_id);
+pub fn default_specialist_priority() -> Vec<SpecialistCategory> {
+    vec![
+        SpecialistCategory::ServiceBuildings,
+        SpecialistCategory::Fabricators,
+        SpecialistCategory::ProcessingPlants,
+        SpecialistCategory::Zones,
+        SpecialistCategory::Habitation,
+        SpecialistCategory::Research,
+    ]
+}
+
+pub fn move_specialist_priority(game_state: &mut GameState, category: SpecialistCategory, raise: bool) {
+    let Some(index) = game_state.specialist_priority.iter().position(|c| *c == category) else {
+        return;
+    };
+    if raise && index > 0 {
+        game_state.specialist_priority.swap(index, index - 1);
+    } else if !raise && index + 1 < game_state.specialist_priority.len() {
+        game_state.specialist_priority.swap(index, index + 1);
     }
 }
 
-pub fn assign_specialists_to_fabricator(game_state: &mut GameState, fab_id: &str, num_to_assign: u32) {
-    if let Some(fab) = game_state.fabricators.iter_mut().find(|f| f.id == fab_id) {
-        if let Some(tier) = fab.available_tiers.get(fab.tier_index) {
-            let available_general_inhabitants = game_state.total_inhabitants.saturating_sub(game_state.assigned_specialists_total);
-            if available_general_inhabitants < num_to_assign {
-                println!("Not enough unassigned inhabitants for Fabricator {}.", fab_id); return;
-            }
-            if game_state.assigned_specialists_total + num_to_assign
-                > game_state.total_specialist_slots
-            {
-                println!("Not enough specialist accommodation available.");
-                return;
-            }
-            if fab.assigned_specialists + num_to_assign > tier.specialist_requirement {
-                println!(
-                    "Cannot assign more specialists than required for Fabricator {}. Max: {}",
-                    fab_id, tier.specialist_requirement
-                );
-                return;
-            }
-            fab.assigned_specialists += num_to_assign;
-            game_state.assigned_specialists_total += num_to_assign;
-            println!("Assigned {} specialists to Fabricator {}.", num_to_assign, fab_id);
-        }
+pub fn toggle_specialist_auto_assign(game_state: &mut GameState) {
+    game_state.specialist_auto_assign = !game_state.specialist_auto_assign;
+    let message = if game_state.specialist_auto_assign {
+        "Specialist auto-assign enabled: open positions are filled as the colony grows."
     } else {
-        println!("Fabricator {} not found.", fab_id);
-    }
+        "Specialist auto-assign disabled."
+    };
+    add_notification(&mut game_state.notifications, message.to_string(), 0.0);
 }
 
-pub fn unassign_specialists_from_fabricator(game_state: &mut GameState, fab_id: &str, num_to_unassign: u32) {
-    if let Some(fab) = game_state.fabricators.iter_mut().find(|f| f.id == fab_id) {
-        let actual_unassign = num_to_unassign.min(fab.assigned_specialists);
-        fab.assigned_specialists -= actual_unassign;
-        game_state.assigned_specialists_total -= actual_unassign;
-        fab.is_active = false;
-        fab.production_progress_secs = 0.0;
-        println!("Unassigned {} specialists from Fabricator {}.", actual_unassign, fab_id);
+pub fn toggle_manual_lock(game_state: &mut GameState, building_id: &str) {
+    let lock = if let Some(fab) = game_state.fabricators.iter_mut().find(|b| b.id == building_id) {
+        &mut fab.manual_lock
+    } else if let Some(plant) = game_state.processing_plants.iter_mut().find(|b| b.id == building_id) {
+        &mut plant.manual_lock
+    } else if let Some(building) = game_state.service_buildings.iter_mut().find(|b| b.id == building_id) {
+        &mut building.manual_lock
+    } else if let Some(zone) = game_state.zones.iter_mut().find(|b| b.id == building_id) {
+        &mut zone.manual_lock
+    } else if let Some(structure) = game_state.habitation_structures.iter_mut().find(|b| b.id == building_id) {
+        &mut structure.manual_lock
+    } else if let Some(institute) = game_state.research_institutes.iter_mut().find(|i| i.id == building_id) {
+        &mut institute.manual_lock
     } else {
-        println!("Fabricator {} not found.", fab_id);
-    }
+        println!("Building with ID {} not found for manual lock.", building_id);
+        return;
+    };
+    *lock = !*lock;
 }
 
-pub fn fabricator_production_system(game_state: &mut GameState, time_delta_secs: f32) {
-    for fab in game_state.fabricators.iter_mut() {
-        if let Some(tier) = fab.available_tiers.get(fab.tier_index) {
-            let has_power = *game_state.current_resources.get(&ResourceType::Power).unwrap_or(&0.0) >= tier.power_requirement as f32;
-            let has_specialists = fab.assigned_specialists >= tier.specialist_requirement;
-            let has_inputs = check_fabricator_inputs(&game_state.current_resources, tier);
+/// Tops `assigned` up towards `capacity` from the free pool.
+fn fill_specialist_positions(assigned: &mut u32, capacity: u32, free: &mut u32) {
+    let added = capacity.saturating_sub(*assigned).min(*free);
+    *assigned += added;
+    *free -= added;
+}
 
-            fab.is_active = has_power && has_specialists && has_inputs;
+/// Fills open specialist positions from unassigned inhabitants in category priority order,
+/// skipping locked and mothballed buildings. Returns the number of specialists assigned.
+pub fn auto_assign_specialists(game_state: &mut GameState) -> u32 {
+    let unassigned = game_state.demographics.working_age.saturating_sub(game_state.assigned_specialists_total);
+    let open_slots = game_state.total_specialist_slots.saturating_sub(game_state.assigned_specialists_total);
+    let mut free = unassigned.min(open_slots);
+    let start = free;
+
+    for category in game_state.specialist_priority.clone() {
+        if free == 0 {
+            break;
+        }
+        fill_specialist_category(game_state, category, &mut free);
+    }
 
-            if fab.is_active {
-                fab.production_progress_secs += time_delta_secs;
-                if fab.production_progress_secs >= tier.production_time_secs {
-                    for (resource_type, required_amount) in &tier.input_resources {
-                        *game_state.current_resources.entry(*resource_type).or_insert(0.0) -= *required_amount as f32;
-                    }
-                    *game_state.current_resources.entry(tier.output_product).or_insert(0.0) += tier.output_quantity as f32;
-                    // println!("Fabricator {} produced {} {} (total now: {}).", fab.id, tier.output_quantity, format!("{:?}", tier.output_product), game_state.current_resources.get(&tier.output_product).unwrap());
-                    fab.production_progress_secs = 0.0;
+    let assigned = start - free;
+    if assigned > 0 {
+        game_state.assigned_specialists_total += assigned;
+        update_total_specialist_slots(game_state);
+    }
+    assigned
+}
+
+/// Fills open positions in one category's unlocked, running buildings from `free`.
+fn fill_specialist_category(game_state: &mut GameState, category: SpecialistCategory, free: &mut u32) {
+    match category {
+        SpecialistCategory::ServiceBuildings => {
+            for building in game_state.service_buildings.iter_mut().filter(|b| !b.manual_lock && !b.is_mothballed) {
+                if let Some(tier) = building.available_tiers.get(building.current_tier_index) {
+                    fill_specialist_positions(&mut building.assigned_specialists, tier.specialist_requirement, free);
+                }
+            }
+        }
+        SpecialistCategory::Fabricators => {
+            for fab in game_state.fabricators.iter_mut().filter(|f| !f.manual_lock && !f.is_mothballed) {
+                if let Some(tier) = fab.available_tiers.get(fab.tier_index) {
+                    fill_specialist_positions(&mut fab.assigned_specialists, tier.specialist_requirement, free);
                 }
             }
         }
+        SpecialistCategory::ProcessingPlants => {
+            for plant in game_state.processing_plants.iter_mut().filter(|p| !p.manual_lock && !p.is_mothballed) {
+                if let Some(tier) = plant.available_tiers.get(plant.tier_index) {
+                    fill_specialist_positions(&mut plant.assigned_specialists, tier.specialist_requirement, free);
+                }
+            }
+        }
+        SpecialistCategory::Zones => {
+            for zone in game_state.zones.iter_mut().filter(|z| !z.manual_lock && !z.is_mothballed) {
+                if let Some(tier) = zone.available_tiers.get(zone.current_tier_index) {
+                    fill_specialist_positions(&mut zone.assigned_specialists, tier.specialist_jobs_provided, free);
+                }
+            }
+        }
+        SpecialistCategory::Habitation => {
+            for structure in game_state.habitation_structures.iter_mut().filter(|h| !h.manual_lock) {
+                if let Some(tier) = structure.available_tiers.get(structure.tier_index) {
+                    let capacity = tier.specialist_slots.min(structure.current_inhabitants);
+                    fill_specialist_positions(&mut structure.assigned_specialists, capacity, free);
+                }
+            }
+        }
+        SpecialistCategory::Research => {
+            for institute in game_state.research_institutes.iter_mut().filter(|i| !i.manual_lock) {
+                fill_specialist_positions(&mut institute.assigned_specialists, RESEARCH_INSTITUTE_SPECIALISTS, free);
+            }
+        }
     }
 }
 
-
-
-pub fn update_civic_index(game_state: &mut GameState) {
-    let mut new_civic_index = 0;
-    for service_building in &game_state.service_buildings {
-        if service_building.is_active {
-            if let Some(tier) = service_building.available_tiers.get(service_building.current_tier_index) {
-                new_civic_index += tier.civic_index_contribution;
-            }
+/// Sends up to `count` specialists back to the general population, starting with the lowest-priority
+/// category and its newest buildings, locked ones included. Each building's count and the colony total
+/// drop together. Returns the number released.
+pub fn release_specialists(game_state: &mut GameState, count: u32) -> u32 {
+    let mut remaining = count;
+    for category in game_state.specialist_priority.clone().into_iter().rev() {
+        if remaining == 0 {
+            break;
         }
-    }
-    for zone in &game_state.zones {
-        if zone.is_active {
-            if let Some(tier) = zone.available_tiers.get(zone.current_tier_index) {
-                new_civic_index += tier.civic_index_contribution;
-            }
+        let posts: Vec<&mut u32> = match category {
+            SpecialistCategory::ServiceBuildings => game_state.service_buildings.iter_mut().map(|b| &mut b.assigned_specialists).collect(),
+            SpecialistCategory::Fabricators => game_state.fabricators.iter_mut().map(|f| &mut f.assigned_specialists).collect(),
+            SpecialistCategory::ProcessingPlants => game_state.processing_plants.iter_mut().map(|p| &mut p.assigned_specialists).collect(),
+            SpecialistCategory::Zones => game_state.zones.iter_mut().map(|z| &mut z.assigned_specialists).collect(),
+            SpecialistCategory::Habitation => game_state.habitation_structures.iter_mut().map(|h| &mut h.assigned_specialists).collect(),
+            SpecialistCategory::Research => game_state.research_institutes.iter_mut().map(|i| &mut i.assigned_specialists).collect(),
+        };
+        for post in posts.into_iter().rev() {
+            let taken = (*post).min(remaining);
+            *post -= taken;
+            remaining -= taken;
         }
     }
-    game_state.civic_index = new_civic_index;
+    let released = count - remaining;
+    game_state.assigned_specialists_total = game_state.assigned_specialists_total.saturating_sub(released);
+    released
 }
 
-static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+/// Releases specialists beyond the working-age population or the colony's specialist slots,
+/// e.g. after deaths, retirement, emigration or demolition.
+pub fn release_excess_specialists(game_state: &mut GameState) {
+    let limit = game_state.demographics.working_age.min(game_state.total_specialist_slots);
+    let excess = game_state.assigned_specialists_total.saturating_sub(limit);
+    if excess > 0 {
+        release_specialists(game_state, excess);
+    }
+}
 
-fn generate_unique_id() -> String {
-    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
-    format!("struct_{}", id)
+/// Unfilled positions in one category's unlocked, running buildings.
+fn open_specialist_positions(game_state: &GameState, category: SpecialistCategory) -> u32 {
+    match category {
+        SpecialistCategory::ServiceBuildings => game_state.service_buildings.iter()
+            .filter(|b| !b.manual_lock && !b.is_mothballed)
+            .filter_map(|b| b.available_tiers.get(b.current_tier_index).map(|t| t.specialist_requirement.saturating_sub(b.assigned_specialists)))
+            .sum(),
+        SpecialistCategory::Fabricators => game_state.fabricators.iter()
+            .filter(|f| !f.manual_lock && !f.is_mothballed)
+            .filter_map(|f| f.available_tiers.get(f.tier_index).map(|t| t.specialist_requirement.saturating_sub(f.assigned_specialists)))
+            .sum(),
+        SpecialistCategory::ProcessingPlants => game_state.processing_plants.iter()
+            .filter(|p| !p.manual_lock && !p.is_mothballed)
+            .filter_map(|p| p.available_tiers.get(p.tier_index).map(|t| t.specialist_requirement.saturating_sub(p.assigned_specialists)))
+            .sum(),
+        SpecialistCategory::Zones => game_state.zones.iter()
+            .filter(|z| !z.manual_lock && !z.is_mothballed)
+            .filter_map(|z| z.available_tiers.get(z.current_tier_index).map(|t| t.specialist_jobs_provided.saturating_sub(z.assigned_specialists)))
+            .sum(),
+        SpecialistCategory::Habitation => game_state.habitation_structures.iter()
+            .filter(|h| !h.manual_lock)
+            .filter_map(|h| h.available_tiers.get(h.tier_index).map(|t| t.specialist_slots.min(h.current_inhabitants).saturating_sub(h.assigned_specialists)))
+            .sum(),
+        SpecialistCategory::Research => game_state.research_institutes.iter()
+            .filter(|i| !i.manual_lock)
+            .map(|i| RESEARCH_INSTITUTE_SPECIALISTS.saturating_sub(i.assigned_specialists))
+            .sum(),
+    }
 }
 
-pub fn get_habitation_tiers() -> Vec<HabitationStructureTier> {
-    vec![
-        HabitationStructureTier {
-            name: "Basic Dwellings".to_string(),
-            housing_capacity: 10,
-            specialist_slots: 1,
-            construction_credits_cost: 100,
-            required_tech: None,
-        },
-        HabitationStructureTier {
-            name: "Community Blocks".to_string(),
-            housing_capacity: 25,
-            specialist_slots: 3,
-            construction_credits_cost: 250,
-            required_tech: None,
-        },
-        HabitationStructureTier {
-            name: "Arcology Spires".to_string(),
-            housing_capacity: 100,
-            specialist_slots: 10,
-            construction_credits_cost: 1000,
-            required_tech: Some(Tech::ArcologyConstruction),
-        },
-    ]
+fn auto_assign_specialists_system(mut game_state: ResMut<GameState>) {
+    if game_state.specialist_auto_assign {
+        auto_assign_specialists(&mut game_state);
+    }
 }
 
-pub fn update_housing_and_specialist_slots(game_state: &mut GameState) {
-    let mut total_housing = 0;
-    let mut total_slots = 0;
-    for structure in &game_state.habitation_structures {
-        if let Some(tier) = structure.available_tiers.get(structure.tier_index) {
-            total_housing += tier.housing_capacity;
-            total_slots += tier.specialist_slots;
+// --- Demographics ---
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AgeCohort {
+    Children,
+    WorkingAge,
+    Elderly,
+}
+
+impl AgeCohort {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AgeCohort::Children => "Children",
+            AgeCohort::WorkingAge => "Working-age",
+            AgeCohort::Elderly => "Elderly",
         }
     }
-    game_state.available_housing_capacity = total_housing;
-    game_state.total_specialist_slots = total_slots;
 }
 
-pub fn add_habitation_structure(
-    game_state: &mut GameState,
-    tier_index: usize,
-    position: Option<(f32, f32)>,
-) {
-    let all_tiers = get_habitation_tiers();
-    if tier_index >= all_tiers.len() {
-        return;
+/// Population split into age cohorts. Only the working-age cohort can be employed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Demographics {
+    pub children: u32,
+    pub working_age: u32,
+    pub elderly: u32,
+    /// Fractional progress towards the next whole coming of age, retirement, death or departure
+    pub maturing_progress: f32,
+    pub retiring_progress: f32,
+    pub death_progress: f32,
+    pub emigration_progress: f32,
+    #[serde(default)]
+    pub starvation_progress: f32,
+    /// Consecutive ticks spent below the emigration happiness threshold
+    pub unhappy_ticks: u32,
+    pub births_last_tick: u32,
+    pub deaths_last_tick: u32,
+    pub emigrants_last_tick: u32,
+}
+
+impl Demographics {
+    pub fn total(&self) -> u32 {
+        self.children + self.working_age + self.elderly
     }
 
-    let tier_info = &all_tiers[tier_index];
-    if let Some(tech) = tier_info.required_tech {
-        if !game_state.unlocked_techs.contains(&tech) {
-            add_notification(
-                &mut game_state.notifications,
-                format!("Requires {:?} to build {}.", tech, tier_info.name),
-                0.0,
-            );
-            return;
+    pub fn cohort(&self, cohort: AgeCohort) -> u32 {
+        match cohort {
+            AgeCohort::Children => self.children,
+            AgeCohort::WorkingAge => self.working_age,
+            AgeCohort::Elderly => self.elderly,
         }
     }
-    if game_state.credits < tier_info.construction_credits_cost as f64 {
-        add_notification(&mut game_state.notifications, format!("Not enough credits to build {}.", tier_info.name), 0.0);
-        return;
-    }
-    game_state.credits -= tier_info.construction_credits_cost as f64;
-    add_notification(&mut game_state.notifications, format!("Built {}.", tier_info.name), 0.0);
-
-    let new_structure = HabitationStructure {
-        id: generate_unique_id(),
-        tier_index,
-        available_tiers: all_tiers.clone(),
-        current_inhabitants: 0,
-        assigned_specialists: 0,
-        position,
-    };
-    game_state.habitation_structures.push(new_structure);
-    update_housing_and_specialist_slots(game_state);
-}
-
-pub fn upgrade_habitation_structure(game_state: &mut GameState, structure_id: &str) {
-    if let Some(structure) = game_state.habitation_structures.iter_mut().find(|s| s.id == structure_id) {
-        if structure.tier_index < structure.available_tiers.len() - 1 {
-            let next_tier_index = structure.tier_index + 1;
-            let next_tier_info = &structure.available_tiers[next_tier_index];
 
-            let upgrade_cost = next_tier_info.construction_credits_cost;
+    fn cohort_mut(&mut self, cohort: AgeCohort) -> &mut u32 {
+        match cohort {
+            AgeCohort::Children => &mut self.children,
+            AgeCohort::WorkingAge => &mut self.working_age,
+            AgeCohort::Elderly => &mut self.elderly,
+        }
+    }
 
-            if game_state.credits < upgrade_cost as f64 {
-                println!("Not enough credits to upgrade Habitation Structure {} to {}. Required: {}, Available: {:.2}", structure_id, next_tier_info.name, upgrade_cost, game_state.credits);
-                return;
-            }
-            game_state.credits -= upgrade_cost as f64;
-            println!("Upgraded Habitation Structure {} to {} for {} credits. Remaining credits: {:.2}", structure_id, next_tier_info.name, upgrade_cost, game_state.credits);
+    /// Removes `count` people, always from the currently largest cohort so the age mix is preserved.
+    /// Returns how many were actually removed.
+    pub fn remove(&mut self, count: u32) -> u32 {
+        let count = count.min(self.total());
+        for _ in 0..count {
+            let largest = [AgeCohort::WorkingAge, AgeCohort::Children, AgeCohort::Elderly]
+                .into_iter()
+                .max_by_key(|c| self.cohort(*c))
+                .unwrap_or(AgeCohort::WorkingAge);
+            *self.cohort_mut(largest) -= 1;
+        }
+        count
+    }
 
-            structure.tier_index = next_tier_index;
-            update_housing_and_specialist_slots(game_state);
-        } else {
-            println!("Habitation Structure {} is already at max tier.", structure_id);
+    /// Brings the cohorts back in line with a head count changed elsewhere, e.g. by demolition or an old save.
+    pub fn reconcile(&mut self, total_inhabitants: u32) {
+        let total = self.total();
+        if total < total_inhabitants {
+            self.working_age += total_inhabitants - total;
+        } else if total > total_inhabitants {
+            self.remove(total - total_inhabitants);
         }
-    } else {
-        println!("Habitation Structure with ID {} not found.", structure_id);
     }
 }
 
-pub fn remove_habitation_structure(game_state: &mut GameState, structure_id: &str) {
-    if let Some(index) = game_state.habitation_structures.iter().position(|s| s.id == structure_id) {
-        let removed_structure = game_state.habitation_structures.remove(index);
-        println!("Removed Habitation Structure with ID {}", structure_id);
+// --- Immigration ---
 
-        update_housing_and_specialist_slots(game_state);
+/// Ticks between scheduled shuttles at each spaceport.
+pub const SHUTTLE_INTERVAL_TICKS: u32 = 60;
+/// Passengers aboard a shuttle when the colony is as attractive as it can be.
+pub const SHUTTLE_CAPACITY: u32 = 20;
+/// Ticks a shuttle waits in orbit for an answer before leaving.
+pub const SHUTTLE_WAIT_TICKS: u32 = 30;
+/// Share of passengers who arrive already trained as specialists.
+const PRE_TRAINED_SHARE: f32 = 0.25;
 
-        game_state.total_inhabitants = game_state.total_inhabitants.saturating_sub(removed_structure.current_inhabitants);
-        game_state.assigned_specialists_total = game_state.assigned_specialists_total.saturating_sub(removed_structure.assigned_specialists);
+/// A shuttle load of would-be colonists waiting for clearance to land.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImmigrationWave {
+    pub id: String,
+    pub spaceport_id: String,
+    /// Passengers without a profession
+    pub inhabitants: u32,
+    /// Pre-trained specialists by profession
+    pub specialists: Vec<(SpecialistCategory, u32)>,
+    pub expires_in: u32,
+}
 
-    } else {
-        println!("Habitation Structure with ID {} not found for removal.", structure_id);
-        return;
+impl ImmigrationWave {
+    pub fn total(&self) -> u32 {
+        self.inhabitants + self.specialists.iter().map(|(_, count)| count).sum::<u32>()
     }
 
-    if game_state.total_inhabitants > game_state.available_housing_capacity {
-        game_state.total_inhabitants = game_state.available_housing_capacity;
+    pub fn describe(&self) -> String {
+        let mut parts = vec![format!("{} inhabitants", self.inhabitants)];
+        parts.extend(self.specialists.iter().map(|(category, count)| format!("{} {}", count, category.profession())));
+        parts.join(", ")
     }
-    game_state.assigned_specialists_total = game_state.assigned_specialists_total.min(game_state.total_inhabitants).min(game_state.total_specialist_slots);
 }
 
-
-
-pub fn assign_specialists_to_structure(game_state: &mut GameState, structure_id: &str, num_to_assign: u32) {
-    if let Some(structure) = game_state.habitation_structures.iter_mut().find(|s| s.id == structure_id) {
-        if let Some(tier) = structure.available_tiers.get(structure.tier_index) {
-            let available_general_inhabitants = game_state.total_inhabitants.saturating_sub(game_state.assigned_specialists_total);
-            if available_general_inhabitants < num_to_assign {
-                println!("Not enough unassigned inhabitants to become specialists.");
-                return;
-            }
-            if game_state.assigned_specialists_total + num_to_assign
-                > game_state.total_specialist_slots
-            {
-                println!("Not enough specialist accommodation available.");
-                return;
-            }
-            if structure.assigned_specialists + num_to_assign > tier.specialist_slots {
-                println!("Not enough specialist slots in this structure.");
-                return;
-            }
-            if structure.assigned_specialists + num_to_assign > structure.current_inhabitants {
-                 println!("Cannot assign more specialists than current inhabitants in the structure.");
-            }
-
-            structure.assigned_specialists += num_to_assign;
-            game_state.assigned_specialists_total += num_to_assign;
-            println!("Assigned {} specialists to structure {}. Total assigned: {}", num_to_assign, structure_id, game_state.assigned_specialists_total);
-        } else {
-            println!("Error: Structure {} tier data missing.", structure_id);
-        }
+/// How appealing the colony looks to prospective settlers, from 0.0 to 1.0,
+/// weighing happiness, free housing and open jobs.
+pub fn colony_attractiveness(game_state: &GameState) -> f32 {
+    let happiness = game_state.colony_happiness / 100.0;
+    let free_housing = if game_state.available_housing_capacity > 0 {
+        game_state.available_housing_capacity.saturating_sub(game_state.total_inhabitants) as f32
+            / game_state.available_housing_capacity as f32
     } else {
-        println!("Habitation Structure with ID {} not found.", structure_id);
-    }
+        0.0
+    };
+    let labor = &game_state.labor_market;
+    let open_jobs = if labor.total_jobs() > 0 {
+        labor.total_jobs().saturating_sub(labor.employed_workers + labor.assigned_specialists) as f32 / labor.total_jobs() as f32
+    } else {
+        0.0
+    };
+    (happiness * 0.4 + free_housing * 0.3 + open_jobs * 0.3).clamp(0.0, 1.0)
 }
 
-pub fn unassign_specialists_from_structure(game_state: &mut GameState, structure_id: &str, num_to_unassign: u32) {
-    if let Some(structure) = game_state.habitation_structures.iter_mut().find(|s| s.id == structure_id) {
-        let actual_unassign = num_to_unassign.min(structure.assigned_specialists);
-        structure.assigned_specialists -= actual_unassign;
-        game_state.assigned_specialists_total -= actual_unassign;
-        println!("Unassigned {} specialists from structure {}. Total assigned: {}", actual_unassign, structure_id, game_state.assigned_specialists_total);
-    } else {
-        println!("Habitation Structure with ID {} not found.", structure_id);
+fn schedule_immigration_wave(game_state: &mut GameState, spaceport_id: String) {
+    let passengers = (SHUTTLE_CAPACITY as f32 * colony_attractiveness(game_state)).round() as u32;
+    if passengers == 0 {
+        return;
     }
-}
 
-pub fn get_service_building_tiers(service_type: ServiceType) -> Vec<ServiceBuildingTier> {
-    match service_type {
-        ServiceType::Wellness => vec![
-            ServiceBuildingTier { name: "Clinic".to_string(), specialist_requirement: 2, service_capacity: 50, service_radius: 50.0, upkeep_cost: 10, civic_index_contribution: 5, construction_credits_cost: 150, required_tech: None },
-            ServiceBuildingTier { name: "Hospital".to_string(), specialist_requirement: 5, service_capacity: 250, service_radius: 75.0, upkeep_cost: 30, civic_index_contribution: 15, construction_credits_cost: 400, required_tech: None },
-        ],
-        ServiceType::Security => vec![
-            ServiceBuildingTier { name: "Security Post".to_string(), specialist_requirement: 3, service_capacity: 50, service_radius: 50.0, upkeep_cost: 15, civic_index_contribution: 5, construction_credits_cost: 150, required_tech: None },
-            ServiceBuildingTier { name: "Precinct".to_string(), specialist_requirement: 7, service_capacity: 250, service_radius: 75.0, upkeep_cost: 40, civic_index_contribution: 15, construction_credits_cost: 450, required_tech: None },
-        ],
-        ServiceType::Education => vec![ ServiceBuildingTier { name: "School".to_string(), specialist_requirement: 4, service_capacity: 100, service_radius: 60.0, upkeep_cost: 25, civic_index_contribution: 10, construction_credits_cost: 300, required_tech: None } ],
-        ServiceType::Recreation => vec![ ServiceBuildingTier { name: "Rec Center".to_string(), specialist_requirement: 3, service_capacity: 100, service_radius: 60.0, upkeep_cost: 20, civic_index_contribution: 8, construction_credits_cost: 250, required_tech: None } ],
-        ServiceType::Spiritual => vec![ ServiceBuildingTier { name: "Sanctum".to_string(), specialist_requirement: 2, service_capacity: 100, service_radius: 60.0, upkeep_cost: 10, civic_index_contribution: 3, construction_credits_cost: 200, required_tech: None } ],
+    // Pre-trained specialists come for the professions the colony is short of, in priority order.
+    let mut trained = (passengers as f32 * PRE_TRAINED_SHARE).floor() as u32;
+    let mut specialists = Vec::new();
+    for category in game_state.specialist_priority.clone() {
+        let count = open_specialist_positions(game_state, category).min(trained);
+        if count > 0 {
+            specialists.push((category, count));
+            trained -= count;
+        }
     }
+    let wave = ImmigrationWave {
+        id: generate_unique_id(),
+        spaceport_id,
+        inhabitants: passengers - specialists.iter().map(|(_, count)| count).sum::<u32>(),
+        specialists,
+        expires_in: SHUTTLE_WAIT_TICKS,
+    };
+
+    let message = format!("Shuttle requesting clearance to land: {}.", wave.describe());
+    let actions = vec![
+        NotificationAction { label: "Accept".to_string(), command: NotificationCommand::AcceptImmigrationWave(wave.id.clone()) },
+        NotificationAction { label: "Refuse".to_string(), command: NotificationCommand::RefuseImmigrationWave(wave.id.clone()) },
+    ];
+    game_state.pending_immigration.push(wave);
+    add_notification_with_actions(&mut game_state.notifications, message, 0.0, actions);
 }
 
-pub fn add_service_building(game_state: &mut GameState, service_type: ServiceType, tier_index: usize, position: Option<(f32, f32)>) {
-    let all_tiers = get_service_building_tiers(service_type);
-    if tier_index >= all_tiers.len() {
-        println!("Error: Invalid tier index for service building type {:?}.", service_type);
+pub fn accept_immigration_wave(game_state: &mut GameState, wave_id: &str) {
+    let Some(index) = game_state.pending_immigration.iter().position(|w| w.id == wave_id) else {
+        println!("Immigration wave {} not found.", wave_id);
         return;
+    };
+    let mut wave = game_state.pending_immigration.remove(index);
+    clear_notification_actions(&mut game_state.notifications, wave_id);
+
+    // Only as many land as there are free homes; unskilled passengers give up their seats first.
+    let free_housing = game_state.available_housing_capacity.saturating_sub(game_state.total_inhabitants);
+    let turned_away = wave.total().saturating_sub(free_housing);
+    let mut excess = turned_away;
+    let cut = excess.min(wave.inhabitants);
+    wave.inhabitants -= cut;
+    excess -= cut;
+    for (_, count) in wave.specialists.iter_mut().rev() {
+        let cut = excess.min(*count);
+        *count -= cut;
+        excess -= cut;
     }
-    let tier_info = &all_tiers[tier_index];
-    if let Some(tech) = tier_info.required_tech {
-        if !game_state.unlocked_techs.contains(&tech) {
-            add_notification(
-                &mut game_state.notifications,
-                format!("Requires {:?} to build {}.", tech, tier_info.name),
-                0.0,
-            );
-            return;
-        }
+
+    // Immigrants arrive as working-age adults.
+    let arrivals = wave.total();
+    game_state.demographics.working_age += arrivals;
+    game_state.total_inhabitants += arrivals;
+
+    // Pre-trained specialists go straight to open positions in their profession.
+    update_total_specialist_slots(game_state);
+    let mut placed = 0;
+    for (category, count) in &wave.specialists {
+        let open_slots = game_state.total_specialist_slots.saturating_sub(game_state.assigned_specialists_total + placed);
+        let mut free = (*count).min(open_slots);
+        let start = free;
+        fill_specialist_category(game_state, *category, &mut free);
+        placed += start - free;
     }
-    if game_state.credits < tier_info.construction_credits_cost as f64 {
-        add_notification(&mut game_state.notifications, format!("Not enough credits to build {:?} - {}.", service_type, tier_info.name), 0.0);
-        return;
+    if placed > 0 {
+        game_state.assigned_specialists_total += placed;
+        update_total_specialist_slots(game_state);
     }
-    game_state.credits -= tier_info.construction_credits_cost as f64;
-    add_notification(&mut game_state.notifications, format!("Built {:?} - {}.", service_type, tier_info.name), 0.0);
 
-    let new_building = ServiceBuilding {
-        id: generate_unique_id(),
-        service_type,
-        current_tier_index: tier_index,
-        available_tiers: all_tiers.clone(),
-        assigned_specialists: 0,
-        is_active: true,
-        position,
-    };
-    game_state.service_buildings.push(new_building);
-    update_civic_index(game_state);
-    if service_type == ServiceType::Education {
-        update_total_specialist_slots(game_state);
+    add_notification(
+        &mut game_state.notifications,
+        format!("Shuttle landed: {} colonists arrived ({} specialists placed).", arrivals, placed),
+        0.0,
+    );
+    if turned_away > 0 {
+        add_notification(
+            &mut game_state.notifications,
+            format!("{} colonists were turned away for lack of housing.", turned_away),
+            0.0,
+        );
     }
-    // Removed the println! for "Added Service Building" as the "Built..." notification covers it.
 }
 
-pub fn upgrade_service_building(game_state: &mut GameState, building_id: &str) {
-    let mut upgraded = false;
-    if let Some(building) = game_state.service_buildings.iter_mut().find(|b| b.id == building_id) {
-        if building.current_tier_index < building.available_tiers.len() - 1 {
-            let next_tier_index = building.current_tier_index + 1;
-            let upgrade_cost = building.available_tiers[next_tier_index].construction_credits_cost;
-            let next_tier_name = building.available_tiers[next_tier_index].name.clone();
-            let specialist_requirement_next_tier = building.available_tiers[next_tier_index].specialist_requirement;
+pub fn refuse_immigration_wave(game_state: &mut GameState, wave_id: &str) {
+    let Some(index) = game_state.pending_immigration.iter().position(|w| w.id == wave_id) else {
+        println!("Immigration wave {} not found.", wave_id);
+        return;
+    };
+    game_state.pending_immigration.remove(index);
+    clear_notification_actions(&mut game_state.notifications, wave_id);
+    add_notification(&mut game_state.notifications, "Shuttle refused landing clearance.".to_string(), 0.0);
+}
 
-            if game_state.credits < upgrade_cost as f64 {
-                println!("Not enough credits to upgrade Service Building {} to {}. Required: {}, Available: {:.2}", building_id, next_tier_name, upgrade_cost, game_state.credits);
-            } else {
-                game_state.credits -= upgrade_cost as f64;
-                println!("Upgraded Service Building {} to {} for {} credits. Remaining credits: {:.2}", building_id, next_tier_name, upgrade_cost, game_state.credits);
+fn spaceport_arrivals_system(mut game_state: ResMut<GameState>) {
+    let game_state = &mut *game_state;
 
-                building.current_tier_index = next_tier_index;
-                if building.assigned_specialists > specialist_requirement_next_tier {
-                    let to_unassign = building.assigned_specialists - specialist_requirement_next_tier;
-                    building.assigned_specialists -= to_unassign;
-                    game_state.assigned_specialists_total = game_state.assigned_specialists_total.saturating_sub(to_unassign);
-                }
-                println!("Service Building {} successfully upgraded to: {}", building_id, next_tier_name);
-                upgraded = true;
-            }
-        } else {
-            println!("Service Building {} is already at max tier.", building_id);
+    let mut departed = Vec::new();
+    for wave in game_state.pending_immigration.iter_mut() {
+        wave.expires_in = wave.expires_in.saturating_sub(1);
+        if wave.expires_in == 0 {
+            departed.push(wave.id.clone());
         }
-    } else {
-        println!("Service Building with ID {} not found.", building_id);
+    }
+    for wave_id in departed {
+        game_state.pending_immigration.retain(|w| w.id != wave_id);
+        clear_notification_actions(&mut game_state.notifications, &wave_id);
+        add_notification(&mut game_state.notifications, "A shuttle left orbit without landing clearance.".to_string(), 0.0);
     }
 
-    if upgraded {
-        update_civic_index(game_state);
-        if let Some(building) = game_state.service_buildings.iter().find(|b| b.id == building_id) {
-            if building.service_type == ServiceType::Education {
-                update_total_specialist_slots(game_state);
-            }
+    let mut due = Vec::new();
+    for spaceport in game_state.spaceports.iter_mut() {
+        spaceport.next_shuttle_in = spaceport.next_shuttle_in.saturating_sub(1);
+        if spaceport.next_shuttle_in == 0 {
+            spaceport.next_shuttle_in = SHUTTLE_INTERVAL_TICKS;
+            due.push(spaceport.id.clone());
+        }
+    }
+    for spaceport_id in due {
+        // One shuttle per spaceport can hold in orbit at a time.
+        if !game_state.pending_immigration.iter().any(|w| w.spaceport_id == spaceport_id) {
+            schedule_immigration_wave(game_state, spaceport_id);
         }
     }
 }
 
-pub fn remove_service_building(game_state: &mut GameState, building_id: &str) {
-    if let Some(index) = game_state.service_buildings.iter().position(|b| b.id == building_id) {
-        let removed_building = game_state.service_buildings.remove(index);
-        game_state.assigned_specialists_total = game_state.assigned_specialists_total.saturating_sub(removed_building.assigned_specialists);
-        update_civic_index(game_state);
-        if removed_building.service_type == ServiceType::Education {
-            update_total_specialist_slots(game_state);
+// --- Labor Market ---
+
+/// Unemployment above this share of the population starts to hurt happiness.
+pub const UNEMPLOYMENT_TOLERANCE: f32 = 0.05;
+
+/// Where every working-age inhabitant stands in the labor market, plus the job supply they compete for.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct LaborMarket {
+    pub employed_workers: u32,
+    pub assigned_specialists: u32,
+    pub unemployed: u32,
+    pub homeless: u32,
+    /// Full crews for every extractor, bio-dome and research institute
+    pub worker_jobs: u32,
+    /// Specialist positions in services, fabricators, processing plants and zones
+    pub specialist_jobs: u32,
+}
+
+impl LaborMarket {
+    pub fn total_jobs(&self) -> u32 {
+        self.worker_jobs + self.specialist_jobs
+    }
+
+    pub fn unemployment_rate(&self) -> f32 {
+        let population = self.employed_workers + self.assigned_specialists + self.unemployed;
+        if population == 0 {
+            0.0
+        } else {
+            self.unemployed as f32 / population as f32
         }
-        println!("Removed Service Building with ID {}", building_id);
-    } else {
-        println!("Service Building with ID {} not found for removal.", building_id);
     }
 }
 
-pub fn assign_specialists_to_service_building(game_state: &mut GameState, building_id: &str, num_to_assign: u32) {
-    if let Some(building) = game_state.service_buildings.iter_mut().find(|b| b.id == building_id) {
+pub fn calculate_labor_market(game_state: &GameState) -> LaborMarket {
+    let worker_jobs = game_state.extractors.len() as u32 * EXTRACTOR_CREW
+        + game_state.bio_domes.len() as u32 * BIO_DOME_CREW
+        + game_state.research_institutes.len() as u32 * RESEARCH_INSTITUTE_CREW;
+
+    let mut specialist_jobs = 0;
+    for building in game_state.service_buildings.iter().filter(|b| !b.is_mothballed) {
         if let Some(tier) = building.available_tiers.get(building.current_tier_index) {
-            let available_general_inhabitants = game_state.total_inhabitants.saturating_sub(game_state.assigned_specialists_total);
-            if available_general_inhabitants < num_to_assign {
-                println!(
-                    "Not enough unassigned inhabitants to become specialists for service building {}.",
-                    building_id
-                );
-                return;
-            }
-            if game_state.assigned_specialists_total + num_to_assign
-                > game_state.total_specialist_slots
-            {
-                println!("Not enough specialist accommodation available.");
-                return;
-            }
-            if building.assigned_specialists + num_to_assign > tier.specialist_requirement {
-                println!(
-                    "Cannot assign more specialists than required for service building {}. Max: {}",
-                    building_id, tier.specialist_requirement
-                );
-                return;
-            }
-            building.assigned_specialists += num_to_assign;
-            game_state.assigned_specialists_total += num_to_assign;
-            println!("Assigned {} specialists to service building {}. Total assigned globally: {}", num_to_assign, building_id, game_state.assigned_specialists_total);
-            if building.service_type == ServiceType::Education {
-                update_total_specialist_slots(game_state);
-            }
+            specialist_jobs += tier.specialist_requirement;
         }
-    } else {
-        println!("Service Building {} not found for specialist assignment.", building_id);
     }
-}
-
-pub fn unassign_specialists_from_service_building(game_state: &mut GameState, building_id: &str, num_to_unassign: u32) {
-    if let Some(building) = game_state.service_buildings.iter_mut().find(|b| b.id == building_id) {
-        let actual_unassign = num_to_unassign.min(building.assigned_specialists);
-        building.assigned_specialists -= actual_unassign;
-        game_state.assigned_specialists_total -= actual_unassign;
-        println!("Unassigned {} specialists from service building {}. Total assigned globally: {}", actual_unassign, building_id, game_state.assigned_specialists_total);
-        if building.service_type == ServiceType::Education {
-            update_total_specialist_slots(game_state);
+    for fab in game_state.fabricators.iter().filter(|f| !f.is_mothballed) {
+        if let Some(tier) = fab.available_tiers.get(fab.tier_index) {
+            specialist_jobs += tier.specialist_requirement;
         }
-    } else {
-        println!("Service Building {} not found for specialist unassignment.", building_id);
     }
-}
-
-pub fn get_zone_tiers(zone_type: ZoneType) -> Vec<ZoneTier> {
-    match zone_type {
+    for plant in game_state.processing_plants.iter().filter(|p| !p.is_mothballed) {
+        if let Some(tier) = plant.available_tiers.get(plant.tier_index) {
+            specialist_jobs += tier.specialist_requirement;
+        }
+    }
+    for zone in game_state.zones.iter().filter(|z| !z.is_mothballed) {
+        if let Some(tier) = zone.available_tiers.get(zone.current_tier_index) {
+            specialist_jobs += tier.specialist_jobs_provided;
+        }
+    }
+    specialist_jobs += game_state.research_institutes.len() as u32 * RESEARCH_INSTITUTE_SPECIALISTS;
+
+    let working_age = game_state.demographics.working_age;
+    let assigned_specialists = game_state.assigned_specialists_total.min(working_age);
+    let employed_workers = game_state.assigned_workforce.min(working_age - assigned_specialists);
+    LaborMarket {
+        employed_workers,
+        assigned_specialists,
+        unemployed: working_age - assigned_specialists - employed_workers,
+        homeless: game_state.total_inhabitants.saturating_sub(game_state.available_housing_capacity),
+        worker_jobs,
+        specialist_jobs,
+    }
+}
+
+fn labor_market_system(mut game_state: ResMut<GameState>) {
+    game_state.labor_market = calculate_labor_market(&game_state);
+}
+
+pub fn toggle_agent_mode(game_state: &mut GameState) {
+    game_state.agent_mode = !game_state.agent_mode;
+    let message = if game_state.agent_mode {
+        "Agent mode enabled: every colonist is now simulated individually."
+    } else {
+        "Agent mode disabled: population is simulated in aggregate."
+    };
+    add_notification(&mut game_state.notifications, message.to_string(), 0.0);
+}
+
+// --- Building Status ---
+
+/// Why a building is or isn't working, recomputed every tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum BuildingStatus {
+    Operational,
+    Unpowered,
+    Understaffed,
+    MissingInputs,
+    OutputStorageFull,
+    UnpaidUpkeep,
+    Mothballed,
+    /// Newly placed and not yet evaluated by a tick
+    #[default]
+    UnderConstruction,
+}
+
+impl BuildingStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BuildingStatus::Operational => "Operational",
+            BuildingStatus::Unpowered => "Unpowered",
+            BuildingStatus::Understaffed => "Understaffed",
+            BuildingStatus::MissingInputs => "Missing Inputs",
+            BuildingStatus::OutputStorageFull => "Output Storage Full",
+            BuildingStatus::UnpaidUpkeep => "Unpaid Upkeep",
+            BuildingStatus::Mothballed => "Mothballed",
+            BuildingStatus::UnderConstruction => "Under Construction",
+        }
+    }
+}
+
+fn staffed_building_status(is_staffed: bool, has_power: bool, output_full: bool) -> BuildingStatus {
+    if !is_staffed {
+        BuildingStatus::Understaffed
+    } else if !has_power {
+        BuildingStatus::Unpowered
+    } else if output_full {
+        BuildingStatus::OutputStorageFull
+    } else {
+        BuildingStatus::Operational
+    }
+}
+
+/// Status of every building in the colony, paired with its ID.
+pub fn building_statuses(game_state: &GameState) -> Vec<(&str, BuildingStatus)> {
+    let mut statuses: Vec<(&str, BuildingStatus)> = Vec::new();
+    statuses.extend(game_state.extractors.iter().map(|b| (b.id.as_str(), b.status)));
+    statuses.extend(game_state.bio_domes.iter().map(|b| (b.id.as_str(), b.status)));
+    statuses.extend(game_state.research_institutes.iter().map(|b| (b.id.as_str(), b.status)));
+    statuses.extend(game_state.fabricators.iter().map(|b| (b.id.as_str(), b.status)));
+    statuses.extend(game_state.processing_plants.iter().map(|b| (b.id.as_str(), b.status)));
+    statuses.extend(game_state.zones.iter().map(|b| (b.id.as_str(), b.status)));
+    statuses.extend(game_state.service_buildings.iter().map(|b| (b.id.as_str(), b.status)));
+    statuses
+}
+
+// --- Data-Driven Building Structs ---
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ExtractorData {
+    pub id: String,
+    pub is_staffed: bool,
+    #[serde(default)]
+    pub assigned_workers: u32,
+    #[serde(default)]
+    pub work_priority: WorkPriority,
+    #[serde(default)]
+    pub status: BuildingStatus,
+    #[serde(default)]
+    pub position: Option<(f32, f32)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BioDomeData {
+    pub id: String,
+    pub is_staffed: bool,
+    #[serde(default)]
+    pub assigned_workers: u32,
+    #[serde(default)]
+    pub work_priority: WorkPriority,
+    #[serde(default)]
+    pub status: BuildingStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PowerRelayData {
+    pub id: String,
+    #[serde(default)]
+    pub position: Option<(f32, f32)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ResearchInstituteData {
+    pub id: String,
+    pub is_staffed: bool,
+    #[serde(default)]
+    pub assigned_workers: u32,
+    #[serde(default)]
+    pub work_priority: WorkPriority,
+    #[serde(default)]
+    pub status: BuildingStatus,
+    #[serde(default)]
+    pub assigned_specialists: u32,
+    /// Excluded from automatic specialist assignment
+    #[serde(default)]
+    pub manual_lock: bool,
+    #[serde(default)]
+    pub field: ResearchField,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StorageSiloData {
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SpaceportData {
+    pub id: String,
+    /// Ticks until the next scheduled shuttle docks
+    pub next_shuttle_in: u32,
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameState {
+    pub administrative_spire: Option<AdministrativeSpire>,
+    pub legacy_structure: Option<LegacyStructure>,
+    pub current_development_phase: DevelopmentPhase,
+    pub current_resources: ResourceBag,
+    pub building_costs: HashMap<BuildingType, ResourceBag>,
+    pub unlocked_techs: HashSet<Tech>,
+    /// Points each field has put into each queued tech
+    #[serde(default, rename = "researchFieldProgress")]
+    pub research_progress: HashMap<Tech, HashMap<ResearchField, f32>>,
+    /// Single-project progress from saves made before research fields; folded into the queue on load
+    #[serde(default, rename = "researchProgress", skip_serializing)]
+    pub legacy_research_progress: Option<(Tech, f32)>,
+    /// Paid-for techs in priority order; each field's points go to the first one still needing them
+    #[serde(default)]
+    pub research_queue: Vec<Tech>,
+    /// Techs whose eureka milestone has been reached
+    #[serde(default)]
+    pub eureka_claimed: HashSet<Tech>,
+    pub tech_costs: HashMap<Tech, u32>,
+    pub habitation_structures: Vec<HabitationStructure>,
+    pub total_inhabitants: u32,
+    /// Accumulator for fractional population growth between ticks
+    #[serde(default)]
+    pub population_growth_progress: f32,
+    pub assigned_workforce: u32,
+    pub available_housing_capacity: u32,
+    pub total_specialist_slots: u32,
+    pub assigned_specialists_total: u32,
+    pub service_buildings: Vec<ServiceBuilding>,
+    pub zones: Vec<Zone>,
+    pub civic_index: u32,
+    pub colony_happiness: f32,
+    /// Happiness of each habitation structure's residents, keyed by structure id
+    #[serde(default)]
+    pub household_happiness: HashMap<String, f32>,
+    #[serde(default)]
+    pub modifiers: ModifierStack,
+    pub simulated_has_sufficient_nutrient_paste: bool,
+    pub fabricators: Vec<FabricatorData>,
+    pub processing_plants: Vec<ProcessingPlantData>,
+    pub unlocked_raw_materials: HashSet<ResourceType>,
+    pub credits: f64,
+    pub total_generated_power: f32,
+    pub total_consumed_power: f32,
+    pub notifications: VecDeque<NotificationEvent>,
+    #[serde(default)]
+    pub tax_rates: TaxRates,
+    #[serde(default = "default_upkeep_priority")]
+    pub upkeep_priority: Vec<UpkeepCategory>,
+    #[serde(default = "default_workforce_priority")]
+    pub workforce_priority: Vec<WorkforceCategory>,
+    #[serde(default)]
+    pub workforce_auto_balance: bool,
+    #[serde(default = "default_specialist_priority")]
+    pub specialist_priority: Vec<SpecialistCategory>,
+    #[serde(default)]
+    pub specialist_auto_assign: bool,
+    #[serde(default)]
+    pub labor_market: LaborMarket,
+    /// Simulates one entity per colonist instead of a bare head count
+    #[serde(default)]
+    pub agent_mode: bool,
+    #[serde(default)]
+    pub demographics: Demographics,
+    #[serde(default)]
+    pub loans: Vec<Loan>,
+    #[serde(default)]
+    pub financial_status: FinancialStatus,
+    /// Cycles spent bankrupt; drives escalating consequences
+    #[serde(default)]
+    pub bankruptcy_cycles: u32,
+    #[serde(default)]
+    pub ledger: Ledger,
+    #[serde(default)]
+    pub resource_accounts: ResourceAccounts,
+    
+    // New data-driven building lists
+    pub extractors: Vec<ExtractorData>,
+    pub bio_domes: Vec<BioDomeData>,
+    pub power_relays: Vec<PowerRelayData>,
+    pub research_institutes: Vec<ResearchInstituteData>,
+    pub storage_silos: Vec<StorageSiloData>,
+    #[serde(default)]
+    pub spaceports: Vec<SpaceportData>,
+    /// Shuttles waiting in orbit for the player to accept or refuse them
+    #[serde(default)]
+    pub pending_immigration: Vec<ImmigrationWave>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NotificationEvent {
+    pub message: String,
+    pub timestamp: f64,
+    /// Choices offered to the player; cleared once one is taken
+    #[serde(default)]
+    pub actions: Vec<NotificationAction>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NotificationCommand {
+    AcceptImmigrationWave(String),
+    RefuseImmigrationWave(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NotificationAction {
+    pub label: String,
+    pub command: NotificationCommand,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FabricatorTier {
+    pub name: String,
+    pub input_resources: ResourceBag,
+    pub output_product: ResourceType,
+    pub output_quantity: u32,
+    pub production_time_secs: f32,
+    pub power_requirement: u32,
+    pub specialist_requirement: u32,
+    pub construction_credits_cost: u32,
+    pub upkeep_cost: u32,
+    #[serde(default)]
+    pub required_tech: Option<Tech>,
+}
+
+impl FabricatorTier {
+    pub fn outputs(&self) -> ResourceBag {
+        ResourceBag::from([(self.output_product, self.output_quantity as f32)])
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct FabricatorData {
+    pub id: String,
+    pub tier_index: usize,
+    pub available_tiers: Vec<FabricatorTier>,
+    pub assigned_specialists: u32,
+    pub is_active: bool,
+    #[serde(default)]
+    pub is_mothballed: bool,
+    #[serde(default)]
+    pub status: BuildingStatus,
+    #[serde(default)]
+    pub manual_lock: bool,
+    pub production_progress_secs: f32,
+    #[serde(default)]
+    pub position: Option<(f32, f32)>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProcessingPlantTier {
+    pub name: String,
+    pub unlocks_resource: Option<ResourceType>,
+    pub input_resource: Option<(ResourceType, u32)>,
+    pub output_resource: Option<(ResourceType, u32)>,
+    pub processing_rate_per_sec: Option<f32>,
+    pub power_requirement: u32,
+    pub specialist_requirement: u32,
+    pub construction_credits_cost: u32,
+    pub upkeep_cost: u32,
+}
+
+impl ProcessingPlantTier {
+    pub fn batch_inputs(&self, batches: f32) -> ResourceBag {
+        self.input_resource.iter().map(|(resource, amount)| (*resource, *amount as f32 * batches)).collect()
+    }
+
+    pub fn batch_outputs(&self, batches: f32) -> ResourceBag {
+        self.output_resource.iter().map(|(resource, amount)| (*resource, *amount as f32 * batches)).collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProcessingPlantData {
+    pub id: String,
+    pub tier_index: usize,
+    pub available_tiers: Vec<ProcessingPlantTier>,
+    pub assigned_specialists: u32,
+    pub is_active: bool,
+    #[serde(default)]
+    pub is_mothballed: bool,
+    #[serde(default)]
+    pub status: BuildingStatus,
+    #[serde(default)]
+    pub manual_lock: bool,
+    pub processing_progress: f32,
+    #[serde(default)]
+    pub position: Option<(f32, f32)>,
+}
+
+#[derive(Resource, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct ColonyStats {
+    pub total_housing: u32,
+    pub total_jobs: u32,
+    pub unemployed: u32,
+    pub homeless: u32,
+    pub children: u32,
+    pub working_age: u32,
+    pub elderly: u32,
+    pub happiness: f32,
+    pub credits: f64,
+    pub net_power: f32,
+    pub nutrient_paste: f32,
+}
+
+
+
+#[derive(Resource, Serialize, Deserialize, Clone)]
+pub struct ServiceCoverage {
+    /// Population-weighted coverage across the whole colony
+    pub coverage: HashMap<ServiceType, f32>,
+    /// Coverage of each habitation structure's residents, keyed by structure id
+    #[serde(default)]
+    pub by_structure: HashMap<String, HashMap<ServiceType, f32>>,
+}
+
+impl Default for ServiceCoverage {
+    fn default() -> Self {
+        ServiceCoverage { coverage: HashMap::new(), by_structure: HashMap::new() }
+    }
+}
+
+impl ServiceCoverage {
+    /// Coverage of one structure for a single service, or averaged over all services when `service` is `None`.
+    pub fn structure_coverage(&self, structure_id: &str, service: Option<ServiceType>) -> f32 {
+        let Some(by_service) = self.by_structure.get(structure_id) else {
+            return 0.0;
+        };
+        match service {
+            Some(service_type) => by_service.get(&service_type).copied().unwrap_or(0.0),
+            None => ALL_SERVICE_TYPES.iter().map(|s| by_service.get(s).copied().unwrap_or(0.0)).sum::<f32>() / ALL_SERVICE_TYPES.len() as f32,
+        }
+    }
+}
+
+#[derive(Resource, Default, Serialize, Deserialize, Clone)]
+pub struct GraphData {
+    pub history: VecDeque<ColonyStats>,
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        let mut building_costs = HashMap::new();
+        building_costs.insert(BuildingType::Extractor, ResourceBag::from([(ResourceType::FerrocreteOre, 75.0)]));
+        building_costs.insert(BuildingType::BioDome, ResourceBag::from([(ResourceType::FerrocreteOre, 50.0)]));
+        building_costs.insert(BuildingType::PowerRelay, ResourceBag::from([(ResourceType::FerrocreteOre, 60.0)]));
+        building_costs.insert(BuildingType::StorageSilo, ResourceBag::from([(ResourceType::FerrocreteOre, 100.0)]));
+        building_costs.insert(BuildingType::ResearchInstitute, ResourceBag::from([(ResourceType::FerrocreteOre, 150.0)]));
+        building_costs.insert(BuildingType::Fabricator, ResourceBag::from([(ResourceType::FerrocreteOre, 200.0)]));
+        building_costs.insert(BuildingType::ProcessingPlant, ResourceBag::from([(ResourceType::FerrocreteOre, 180.0)]));
+        building_costs.insert(BuildingType::Spaceport, ResourceBag::from([(ResourceType::FerrocreteOre, 250.0), (ResourceType::CuprumDeposits, 50.0)]));
+
+        let mut tech_costs = HashMap::new();
+        tech_costs.insert(Tech::BasicConstructionProtocols, 100);
+        tech_costs.insert(Tech::EfficientExtraction, 250);
+        tech_costs.insert(Tech::AdvancedFabrication, 500);
+        tech_costs.insert(Tech::IndustrialProcessing, 500);
+        tech_costs.insert(Tech::ZoningOrdinances, 400);
+        tech_costs.insert(Tech::ArcologyConstruction, 1000);
+
+
+        let current_resources = ResourceBag::from([
+            (ResourceType::NutrientPaste, 50.0),
+            (ResourceType::FerrocreteOre, 200.0),
+            (ResourceType::CuprumDeposits, 50.0),
+            (ResourceType::Power, 100.0),
+            (ResourceType::ManufacturedGoods, 0.0),
+            (ResourceType::AdvancedComponents, 0.0),
+            (ResourceType::RefinedXylos, 0.0),
+            (ResourceType::ProcessedQuantium, 0.0),
+            (ResourceType::RawXylos, 0.0),
+            (ResourceType::RawQuantium, 0.0),
+        ]);
+
+        let mut new_state = Self {
+            administrative_spire: None,
+            legacy_structure: None,
+            current_development_phase: DevelopmentPhase::default(),
+            current_resources,
+            building_costs,
+            unlocked_techs: HashSet::new(),
+            research_progress: HashMap::new(),
+            legacy_research_progress: None,
+            research_queue: Vec::new(),
+            eureka_claimed: HashSet::new(),
+            tech_costs,
+            habitation_structures: Vec::new(),
+            total_inhabitants: 5,
+            population_growth_progress: 0.0,
+            assigned_workforce: 0,
+            available_housing_capacity: 0,
+            total_specialist_slots: 0,
+            assigned_specialists_total: 0,
+            service_buildings: Vec::new(),
+            zones: Vec::new(),
+            civic_index: 0,
+            colony_happiness: 50.0,
+            household_happiness: HashMap::new(),
+            modifiers: ModifierStack::default(),
+            simulated_has_sufficient_nutrient_paste: true,
+            fabricators: Vec::new(),
+            processing_plants: Vec::new(),
+            unlocked_raw_materials: HashSet::new(),
+            credits: 10000.0,
+            total_generated_power: 0.0,
+            total_consumed_power: 0.0,
+            notifications: VecDeque::new(),
+            tax_rates: TaxRates::default(),
+            upkeep_priority: default_upkeep_priority(),
+            workforce_priority: default_workforce_priority(),
+            workforce_auto_balance: false,
+            specialist_priority: default_specialist_priority(),
+            specialist_auto_assign: false,
+            labor_market: LaborMarket::default(),
+            agent_mode: false,
+            demographics: Demographics { working_age: 5, ..Default::default() },
+            loans: Vec::new(),
+            financial_status: FinancialStatus::default(),
+            bankruptcy_cycles: 0,
+            ledger: Ledger::default(),
+            resource_accounts: ResourceAccounts::default(),
+            extractors: Vec::new(),
+            bio_domes: Vec::new(),
+            power_relays: Vec::new(),
+            research_institutes: Vec::new(),
+            storage_silos: Vec::new(),
+            spaceports: Vec::new(),
+            pending_immigration: Vec::new(),
+        };
+
+        Self::add_notification_internal(&mut new_state.notifications, "Colony established. Welcome, Commander!".to_string(), 0.0);
+        Self::add_notification_internal(&mut new_state.notifications, "Low power levels detected early in the simulation.".to_string(), 0.1);
+        Self::add_notification_internal(&mut new_state.notifications, "A strange signal was briefly detected from the nearby asteroid belt.".to_string(), 0.2);
+
+        new_state
+    }
+}
+
+impl GameState {
+    fn add_notification_internal(notifications_vec: &mut VecDeque<NotificationEvent>, message: String, timestamp: f64) {
+        notifications_vec.push_front(NotificationEvent {
+            message,
+            timestamp,
+            actions: Vec::new(),
+        });
+        if notifications_vec.len() > 20 {
+            notifications_vec.pop_back();
+        }
+    }
+}
+
+pub fn add_notification(notifications: &mut VecDeque<NotificationEvent>, message: String, current_time_seconds: f64) {
+    GameState::add_notification_internal(notifications, message, current_time_seconds);
+}
+
+pub fn add_notification_with_actions(
+    notifications: &mut VecDeque<NotificationEvent>,
+    message: String,
+    current_time_seconds: f64,
+    actions: Vec<NotificationAction>,
+) {
+    GameState::add_notification_internal(notifications, message, current_time_seconds);
+    if let Some(notification) = notifications.front_mut() {
+        notification.actions = actions;
+    }
+}
+
+/// Removes the buttons from every notification offering a choice about `wave_id`.
+fn clear_notification_actions(notifications: &mut VecDeque<NotificationEvent>, wave_id: &str) {
+    for notification in notifications.iter_mut() {
+        notification.actions.retain(|action| match &action.command {
+            NotificationCommand::AcceptImmigrationWave(id) | NotificationCommand::RefuseImmigrationWave(id) => id != wave_id,
+        });
+    }
+}
+
+pub fn run_notification_command(game_state: &mut GameState, command: &NotificationCommand) {
+    match command {
+        NotificationCommand::AcceptImmigrationWave(id) => accept_immigration_wave(game_state, id),
+        NotificationCommand::RefuseImmigrationWave(id) => refuse_immigration_wave(game_state, id),
+    }
+}
+
+pub fn get_fabricator_tiers() -> Vec<FabricatorTier> {
+    vec![
+        FabricatorTier {
+            name: "Basic Fabricator".to_string(),
+            input_resources: ResourceBag::from([
+                (ResourceType::FerrocreteOre, 2.0),
+                (ResourceType::CuprumDeposits, 1.0),
+            ]),
+            output_product: ResourceType::ManufacturedGoods,
+            output_quantity: 1,
+            production_time_secs: 10.0,
+            power_requirement: 15,
+            specialist_requirement: 1,
+            construction_credits_cost: 200,
+            upkeep_cost: 10,
+            required_tech: None,
+        },
+        FabricatorTier {
+            name: "Advanced Fabricator".to_string(),
+            input_resources: ResourceBag::from([
+                (ResourceType::ManufacturedGoods, 2.0),
+                (ResourceType::RefinedXylos, 1.0),
+            ]),
+            output_product: ResourceType::AdvancedComponents,
+            output_quantity: 1,
+            production_time_secs: 20.0,
+            power_requirement: 30,
+            specialist_requirement: 2,
+            construction_credits_cost: 500,
+            upkeep_cost: 25,
+            required_tech: Some(Tech::AdvancedFabrication),
+        },
+    ]
+}
+
+
+/// Returns the new fabricator's ID, or `None` if it could not be built.
+pub fn add_fabricator(game_state: &mut GameState, tier_index: usize, position: Option<(f32, f32)>) -> Option<String> {
+    let all_tiers = get_fabricator_tiers();
+    if tier_index >= all_tiers.len() {
+        println!("Error: Invalid tier index for fabricator.");
+        return None;
+    }
+    let tier_info = &all_tiers[tier_index];
+    if !game_state.unlocked_techs.contains(&Tech::BasicConstructionProtocols) {
+        add_notification(
+            &mut game_state.notifications,
+            "Requires BasicConstructionProtocols to build Fabricator.".to_string(),
+            0.0,
+        );
+        return None;
+    }
+    if let Some(tech) = tier_info.required_tech.filter(|t| !game_state.unlocked_techs.contains(t)) {
+        add_notification(&mut game_state.notifications, format!("Requires {} to build {}.", tech, tier_info.name), 0.0);
+        return None;
+    }
+    if game_state.credits < tier_info.construction_credits_cost as f64 {
+        add_notification(&mut game_state.notifications, format!("Not enough credits to build {}.", tier_info.name), 0.0);
+        return None;
+    }
+    let new_id = generate_unique_id();
+    game_state.credits -= tier_info.construction_credits_cost as f64;
+    game_state.ledger.record(TransactionCategory::Construction, Some(&new_id), -(tier_info.construction_credits_cost as f64));
+    add_notification(&mut game_state.notifications, format!("Built {}.", tier_info.name), 0.0);
+
+    let new_fabricator = FabricatorData {
+        id: new_id.clone(),
+        tier_index,
+        available_tiers: all_tiers.clone(),
+        assigned_specialists: 0,
+        is_active: false,
+        is_mothballed: false,
+        status: BuildingStatus::UnderConstruction,
+        manual_lock: false,
+        production_progress_secs: 0.0,
+        position,
+    };
+    game_state.fabricators.push(new_fabricator);
+    Some(new_id)
+}
+
+pub fn upgrade_fabricator(game_state: &mut GameState, fabricator_id: &str) {
+    if let Some(fab) = game_state.fabricators.iter_mut().find(|f| f.id == fabricator_id) {
+        if fab.tier_index < fab.available_tiers.len() - 1 {
+            let next_tier_index = fab.tier_index + 1;
+            let next_tier_info = &fab.available_tiers[next_tier_index];
+            let upgrade_cost = next_tier_info.construction_credits_cost;
+            if let Some(tech) = next_tier_info.required_tech.filter(|t| !game_state.unlocked_techs.contains(t)) {
+                add_notification(&mut game_state.notifications, format!("Requires {} to upgrade to {}.", tech, next_tier_info.name), 0.0);
+                return;
+            }
+
+            if game_state.credits < upgrade_cost as f64 {
+                add_notification(&mut game_state.notifications, format!("Not enough credits to upgrade to {}.", next_tier_info.name), 0.0);
+                return;
+            }
+            game_state.credits -= upgrade_cost as f64;
+            game_state.ledger.record(TransactionCategory::Upgrade, Some(fabricator_id), -(upgrade_cost as f64));
+            println!("Upgraded Fabricator {} to {} for {} credits. Remaining credits: {:.2}", fabricator_id, next_tier_info.name, upgrade_cost, game_state.credits);
+
+            fab.tier_index = next_tier_index;
+            fab.production_progress_secs = 0.0;
+
+            if fab.assigned_specialists > next_tier_info.specialist_requirement {
+                let to_unassign = fab.assigned_specialists - next_tier_info.specialist_requirement;
+                fab.assigned_specialists -= to_unassign;
+                game_state.assigned_specialists_total -= to_unassign;
+            }
+            add_notification(&mut game_state.notifications, format!("Upgraded Fabricator to {}.", next_tier_info.name), 0.0);
+        } else {
+            println!("Fabricator {} is already at max tier.", fabricator_id);
+        }
+    } else {
+        println!("Fabricator with ID {} not found.", fabricator_id);
+    }
+}
+
+pub fn remove_fabricator(game_state: &mut GameState, fabricator_id: &str) {
+    if let Some(index) = game_state.fabricators.iter().position(|f| f.id == fabricator_id) {
+        let removed_fab = game_state.fabricators.remove(index);
+        game_state.assigned_specialists_total = game_state.assigned_specialists_total.saturating_sub(removed_fab.assigned_specialists);
+        println!("Removed Fabricator with ID {}", fabricator_id);
+    } else {
+        println!("Fabricator with ID {} not found for removal.", fabricator_id);
+    }
+}
+
+pub fn assign_specialists_to_fabricator(game_state: &mut GameState, fab_id: &str, num_to_assign: u32) {
+    if let Some(fab) = game_state.fabricators.iter_mut().find(|f| f.id == fab_id) {
+        if let Some(tier) = fab.available_tiers.get(fab.tier_index) {
+            let available_general_inhabitants = game_state.demographics.working_age.saturating_sub(game_state.assigned_specialists_total);
+            if available_general_inhabitants < num_to_assign {
+                println!("Not enough unassigned inhabitants for Fabricator {}.", fab_id); return;
+            }
+            if game_state.assigned_specialists_total + num_to_assign
+                > game_state.total_specialist_slots
+            {
+                println!("Not enough specialist accommodation available.");
+                return;
+            }
+            if fab.assigned_specialists + num_to_assign > tier.specialist_requirement {
+                println!(
+                    "Cannot assign more specialists than required for Fabricator {}. Max: {}",
+                    fab_id, tier.specialist_requirement
+                );
+                return;
+            }
+            fab.assigned_specialists += num_to_assign;
+            game_state.assigned_specialists_total += num_to_assign;
+            println!("Assigned {} specialists to Fabricator {}.", num_to_assign, fab_id);
+        }
+    } else {
+        println!("Fabricator {} not found.", fab_id);
+    }
+}
+
+pub fn unassign_specialists_from_fabricator(game_state: &mut GameState, fab_id: &str, num_to_unassign: u32) {
+    if let Some(fab) = game_state.fabricators.iter_mut().find(|f| f.id == fab_id) {
+        let actual_unassign = num_to_unassign.min(fab.assigned_specialists);
+        fab.assigned_specialists -= actual_unassign;
+        game_state.assigned_specialists_total -= actual_unassign;
+        fab.is_active = false;
+        fab.production_progress_secs = 0.0;
+        println!("Unassigned {} specialists from Fabricator {}.", actual_unassign, fab_id);
+    } else {
+        println!("Fabricator {} not found.", fab_id);
+    }
+}
+
+pub fn fabricator_production_system(game_state: &mut GameState, time_delta_secs: f32) {
+    let capacity = BASE_STORAGE_CAPACITY + game_state.storage_silos.len() as f32 * STORAGE_SILO_CAPACITY;
+    let speed = game_state.modifiers.apply(STAT_FABRICATOR_OUTPUT, 1.0);
+    for fab in game_state.fabricators.iter_mut() {
+        if let Some(tier) = fab.available_tiers.get(fab.tier_index) {
+            let has_power = game_state.current_resources.get(ResourceType::Power) >= tier.power_requirement as f32;
+            let staffing = staffing_fraction(fab.assigned_specialists, tier.specialist_requirement);
+            let has_specialists = staffing > 0.0;
+            let has_inputs = game_state.current_resources.can_afford(&tier.input_resources);
+            let output_full = game_state.current_resources.get(tier.output_product) >= capacity;
+
+            fab.is_active = has_power && has_specialists && has_inputs && !output_full && !fab.is_mothballed;
+            fab.status = if fab.is_mothballed {
+                BuildingStatus::Mothballed
+            } else if !has_specialists {
+                BuildingStatus::Understaffed
+            } else if !has_power {
+                BuildingStatus::Unpowered
+            } else if !has_inputs {
+                BuildingStatus::MissingInputs
+            } else if output_full {
+                BuildingStatus::OutputStorageFull
+            } else {
+                BuildingStatus::Operational
+            };
+
+            if fab.is_active {
+                fab.production_progress_secs += time_delta_secs * staffing * speed;
+                if fab.production_progress_secs >= tier.production_time_secs {
+                    let accounts = &mut game_state.resource_accounts;
+                    let stock = &mut game_state.current_resources;
+                    if accounts.withdraw(stock, &tier.input_resources, &fab.id).is_ok() {
+                        accounts.deposit(stock, &tier.outputs(), capacity, &fab.id);
+                    }
+                    // println!("Fabricator {} produced {} {} (total now: {}).", fab.id, tier.output_quantity, format!("{:?}", tier.output_product), game_state.current_resources.get(tier.output_product));
+                    fab.production_progress_secs = 0.0;
+                }
+            }
+        }
+    }
+}
+
+
+
+pub fn update_civic_index(game_state: &mut GameState) {
+    let mut new_civic_index = 0;
+    for service_building in &game_state.service_buildings {
+        if service_building.is_active {
+            if let Some(tier) = service_building.available_tiers.get(service_building.current_tier_index) {
+                new_civic_index += tier.civic_index_contribution;
+            }
+        }
+    }
+    for zone in &game_state.zones {
+        if zone.is_active {
+            if let Some(tier) = zone.available_tiers.get(zone.current_tier_index) {
+                new_civic_index += tier.civic_index_contribution;
+            }
+        }
+    }
+    game_state.civic_index = new_civic_index;
+}
+
+static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+fn generate_unique_id() -> String {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    format!("struct_{}", id)
+}
+
+pub fn get_habitation_tiers() -> Vec<HabitationStructureTier> {
+    vec![
+        HabitationStructureTier {
+            name: "Basic Dwellings".to_string(),
+            housing_capacity: 10,
+            specialist_slots: 1,
+            construction_credits_cost: 100,
+            required_tech: None,
+        },
+        HabitationStructureTier {
+            name: "Community Blocks".to_string(),
+            housing_capacity: 25,
+            specialist_slots: 3,
+            construction_credits_cost: 250,
+            required_tech: None,
+        },
+        HabitationStructureTier {
+            name: "Arcology Spires".to_string(),
+            housing_capacity: 100,
+            specialist_slots: 10,
+            construction_credits_cost: 1000,
+            required_tech: Some(Tech::ArcologyConstruction),
+        },
+    ]
+}
+
+pub fn update_housing_and_specialist_slots(game_state: &mut GameState) {
+    let mut total_housing = 0;
+    let mut total_slots = 0;
+    for structure in &game_state.habitation_structures {
+        if let Some(tier) = structure.available_tiers.get(structure.tier_index) {
+            total_housing += tier.housing_capacity;
+            total_slots += tier.specialist_slots;
+        }
+    }
+    game_state.available_housing_capacity = total_housing;
+    game_state.total_specialist_slots = total_slots;
+}
+
+/// Returns the new structure's ID, or `None` if it could not be built.
+pub fn add_habitation_structure(
+    game_state: &mut GameState,
+    tier_index: usize,
+    position: Option<(f32, f32)>,
+) -> Option<String> {
+    let all_tiers = get_habitation_tiers();
+    if tier_index >= all_tiers.len() {
+        return None;
+    }
+
+    let tier_info = &all_tiers[tier_index];
+    if let Some(tech) = tier_info.required_tech {
+        if !game_state.unlocked_techs.contains(&tech) {
+            add_notification(
+                &mut game_state.notifications,
+                format!("Requires {:?} to build {}.", tech, tier_info.name),
+                0.0,
+            );
+            return None;
+        }
+    }
+    if game_state.credits < tier_info.construction_credits_cost as f64 {
+        add_notification(&mut game_state.notifications, format!("Not enough credits to build {}.", tier_info.name), 0.0);
+        return None;
+    }
+    let new_id = generate_unique_id();
+    game_state.credits -= tier_info.construction_credits_cost as f64;
+    game_state.ledger.record(TransactionCategory::Construction, Some(&new_id), -(tier_info.construction_credits_cost as f64));
+    add_notification(&mut game_state.notifications, format!("Built {}.", tier_info.name), 0.0);
+
+    let new_structure = HabitationStructure {
+        id: new_id.clone(),
+        tier_index,
+        available_tiers: all_tiers.clone(),
+        current_inhabitants: 0,
+        assigned_specialists: 0,
+        position,
+        manual_lock: false,
+    };
+    game_state.habitation_structures.push(new_structure);
+    update_housing_and_specialist_slots(game_state);
+    Some(new_id)
+}
+
+pub fn upgrade_habitation_structure(game_state: &mut GameState, structure_id: &str) {
+    if let Some(structure) = game_state.habitation_structures.iter_mut().find(|s| s.id == structure_id) {
+        if structure.tier_index < structure.available_tiers.len() - 1 {
+            let next_tier_index = structure.tier_index + 1;
+            let next_tier_info = &structure.available_tiers[next_tier_index];
+            if let Some(tech) = next_tier_info.required_tech.filter(|t| !game_state.unlocked_techs.contains(t)) {
+                add_notification(&mut game_state.notifications, format!("Requires {} to upgrade to {}.", tech, next_tier_info.name), 0.0);
+                return;
+            }
+
+            let upgrade_cost = next_tier_info.construction_credits_cost;
+
+            if game_state.credits < upgrade_cost as f64 {
+                println!("Not enough credits to upgrade Habitation Structure {} to {}. Required: {}, Available: {:.2}", structure_id, next_tier_info.name, upgrade_cost, game_state.credits);
+                return;
+            }
+            game_state.credits -= upgrade_cost as f64;
+            game_state.ledger.record(TransactionCategory::Upgrade, Some(structure_id), -(upgrade_cost as f64));
+            println!("Upgraded Habitation Structure {} to {} for {} credits. Remaining credits: {:.2}", structure_id, next_tier_info.name, upgrade_cost, game_state.credits);
+
+            structure.tier_index = next_tier_index;
+            update_housing_and_specialist_slots(game_state);
+            assign_housing(game_state);
+        } else {
+            println!("Habitation Structure {} is already at max tier.", structure_id);
+        }
+    } else {
+        println!("Habitation Structure with ID {} not found.", structure_id);
+    }
+}
+
+pub fn remove_habitation_structure(game_state: &mut GameState, structure_id: &str) {
+    if let Some(index) = game_state.habitation_structures.iter().position(|s| s.id == structure_id) {
+        let removed_structure = game_state.habitation_structures.remove(index);
+        println!("Removed Habitation Structure with ID {}", structure_id);
+
+        update_housing_and_specialist_slots(game_state);
+        game_state.assigned_specialists_total = game_state.assigned_specialists_total.saturating_sub(removed_structure.assigned_specialists);
+
+        // Residents move out into whatever room is left elsewhere; the rest leave the colony.
+        let displaced = removed_structure.current_inhabitants;
+        let leaving = game_state.total_inhabitants.saturating_sub(game_state.available_housing_capacity).min(displaced);
+        game_state.total_inhabitants -= leaving;
+        assign_housing(game_state);
+        if displaced > 0 {
+            add_notification(
+                &mut game_state.notifications,
+                format!("{} residents moved out: {} rehoused, {} left the colony.", displaced, displaced - leaving, leaving),
+                0.0,
+            );
+        }
+    } else {
+        println!("Habitation Structure with ID {} not found for removal.", structure_id);
+        return;
+    }
+
+    if game_state.total_inhabitants > game_state.available_housing_capacity {
+        game_state.total_inhabitants = game_state.available_housing_capacity;
+    }
+    let total_inhabitants = game_state.total_inhabitants;
+    game_state.demographics.reconcile(total_inhabitants);
+    release_excess_specialists(game_state);
+}
+
+/// Distributes the population over habitation structures. Residents stay put while their home
+/// has room; when the population shrinks the newest structures empty first, and newcomers move
+/// into the oldest structures with space. Anyone left over is homeless.
+pub fn assign_housing(game_state: &mut GameState) {
+    let total = game_state.total_inhabitants;
+    let mut housed = 0;
+    for structure in game_state.habitation_structures.iter_mut() {
+        let capacity = structure.available_tiers.get(structure.tier_index).map_or(0, |t| t.housing_capacity);
+        structure.current_inhabitants = structure.current_inhabitants.min(capacity);
+        housed += structure.current_inhabitants;
+    }
+
+    for structure in game_state.habitation_structures.iter_mut().rev() {
+        if housed <= total {
+            break;
+        }
+        let moving_out = (housed - total).min(structure.current_inhabitants);
+        structure.current_inhabitants -= moving_out;
+        housed -= moving_out;
+    }
+
+    for structure in game_state.habitation_structures.iter_mut() {
+        if housed >= total {
+            break;
+        }
+        let capacity = structure.available_tiers.get(structure.tier_index).map_or(0, |t| t.housing_capacity);
+        let moving_in = capacity.saturating_sub(structure.current_inhabitants).min(total - housed);
+        structure.current_inhabitants += moving_in;
+        housed += moving_in;
+    }
+
+    // A structure can't keep more specialists than it has residents.
+    let mut released = 0;
+    for structure in game_state.habitation_structures.iter_mut() {
+        if structure.assigned_specialists > structure.current_inhabitants {
+            released += structure.assigned_specialists - structure.current_inhabitants;
+            structure.assigned_specialists = structure.current_inhabitants;
+        }
+    }
+    game_state.assigned_specialists_total = game_state.assigned_specialists_total.saturating_sub(released);
+}
+
+fn housing_assignment_system(mut game_state: ResMut<GameState>) {
+    assign_housing(&mut game_state);
+}
+
+
+
+pub fn assign_specialists_to_structure(game_state: &mut GameState, structure_id: &str, num_to_assign: u32) {
+    if let Some(structure) = game_state.habitation_structures.iter_mut().find(|s| s.id == structure_id) {
+        if let Some(tier) = structure.available_tiers.get(structure.tier_index) {
+            let available_general_inhabitants = game_state.demographics.working_age.saturating_sub(game_state.assigned_specialists_total);
+            if available_general_inhabitants < num_to_assign {
+                println!("Not enough unassigned inhabitants to become specialists.");
+                return;
+            }
+            if game_state.assigned_specialists_total + num_to_assign
+                > game_state.total_specialist_slots
+            {
+                println!("Not enough specialist accommodation available.");
+                return;
+            }
+            if structure.assigned_specialists + num_to_assign > tier.specialist_slots {
+                println!("Not enough specialist slots in this structure.");
+                return;
+            }
+            if structure.assigned_specialists + num_to_assign > structure.current_inhabitants {
+                 println!("Cannot assign more specialists than current inhabitants in the structure.");
+            }
+
+            structure.assigned_specialists += num_to_assign;
+            game_state.assigned_specialists_total += num_to_assign;
+            println!("Assigned {} specialists to structure {}. Total assigned: {}", num_to_assign, structure_id, game_state.assigned_specialists_total);
+        } else {
+            println!("Error: Structure {} tier data missing.", structure_id);
+        }
+    } else {
+        println!("Habitation Structure with ID {} not found.", structure_id);
+    }
+}
+
+pub fn unassign_specialists_from_structure(game_state: &mut GameState, structure_id: &str, num_to_unassign: u32) {
+    if let Some(structure) = game_state.habitation_structures.iter_mut().find(|s| s.id == structure_id) {
+        let actual_unassign = num_to_unassign.min(structure.assigned_specialists);
+        structure.assigned_specialists -= actual_unassign;
+        game_state.assigned_specialists_total -= actual_unassign;
+        println!("Unassigned {} specialists from structure {}. Total assigned: {}", actual_unassign, structure_id, game_state.assigned_specialists_total);
+    } else {
+        println!("Habitation Structure with ID {} not found.", structure_id);
+    }
+}
+
+pub fn get_service_building_tiers(service_type: ServiceType) -> Vec<ServiceBuildingTier> {
+    match service_type {
+        ServiceType::Wellness => vec![
+            ServiceBuildingTier { name: "Clinic".to_string(), specialist_requirement: 2, service_capacity: 50, service_radius: 50.0, upkeep_cost: 10, civic_index_contribution: 5, construction_credits_cost: 150, required_tech: None },
+            ServiceBuildingTier { name: "Hospital".to_string(), specialist_requirement: 5, service_capacity: 250, service_radius: 75.0, upkeep_cost: 30, civic_index_contribution: 15, construction_credits_cost: 400, required_tech: None },
+        ],
+        ServiceType::Security => vec![
+            ServiceBuildingTier { name: "Security Post".to_string(), specialist_requirement: 3, service_capacity: 50, service_radius: 50.0, upkeep_cost: 15, civic_index_contribution: 5, construction_credits_cost: 150, required_tech: None },
+            ServiceBuildingTier { name: "Precinct".to_string(), specialist_requirement: 7, service_capacity: 250, service_radius: 75.0, upkeep_cost: 40, civic_index_contribution: 15, construction_credits_cost: 450, required_tech: None },
+        ],
+        ServiceType::Education => vec![ ServiceBuildingTier { name: "School".to_string(), specialist_requirement: 4, service_capacity: 100, service_radius: 60.0, upkeep_cost: 25, civic_index_contribution: 10, construction_credits_cost: 300, required_tech: None } ],
+        ServiceType::Recreation => vec![ ServiceBuildingTier { name: "Rec Center".to_string(), specialist_requirement: 3, service_capacity: 100, service_radius: 60.0, upkeep_cost: 20, civic_index_contribution: 8, construction_credits_cost: 250, required_tech: None } ],
+        ServiceType::Spiritual => vec![ ServiceBuildingTier { name: "Sanctum".to_string(), specialist_requirement: 2, service_capacity: 100, service_radius: 60.0, upkeep_cost: 10, civic_index_contribution: 3, construction_credits_cost: 200, required_tech: None } ],
+    }
+}
+
+/// Returns the new building's ID, or `None` if it could not be built.
+pub fn add_service_building(game_state: &mut GameState, service_type: ServiceType, tier_index: usize, position: Option<(f32, f32)>) -> Option<String> {
+    let all_tiers = get_service_building_tiers(service_type);
+    if tier_index >= all_tiers.len() {
+        println!("Error: Invalid tier index for service building type {:?}.", service_type);
+        return None;
+    }
+    let tier_info = &all_tiers[tier_index];
+    if let Some(tech) = tier_info.required_tech {
+        if !game_state.unlocked_techs.contains(&tech) {
+            add_notification(
+                &mut game_state.notifications,
+                format!("Requires {:?} to build {}.", tech, tier_info.name),
+                0.0,
+            );
+            return None;
+        }
+    }
+    if game_state.credits < tier_info.construction_credits_cost as f64 {
+        add_notification(&mut game_state.notifications, format!("Not enough credits to build {:?} - {}.", service_type, tier_info.name), 0.0);
+        return None;
+    }
+    let new_id = generate_unique_id();
+    game_state.credits -= tier_info.construction_credits_cost as f64;
+    game_state.ledger.record(TransactionCategory::Construction, Some(&new_id), -(tier_info.construction_credits_cost as f64));
+    add_notification(&mut game_state.notifications, format!("Built {:?} - {}.", service_type, tier_info.name), 0.0);
+
+    let new_building = ServiceBuilding {
+        id: new_id.clone(),
+        service_type,
+        current_tier_index: tier_index,
+        available_tiers: all_tiers.clone(),
+        assigned_specialists: 0,
+        is_active: true,
+        is_mothballed: false,
+        status: BuildingStatus::UnderConstruction,
+        manual_lock: false,
+        position,
+    };
+    game_state.service_buildings.push(new_building);
+    update_civic_index(game_state);
+    if service_type == ServiceType::Education {
+        update_total_specialist_slots(game_state);
+    }
+    // Removed the println! for "Added Service Building" as the "Built..." notification covers it.
+    Some(new_id)
+}
+
+pub fn upgrade_service_building(game_state: &mut GameState, building_id: &str) {
+    let mut upgraded = false;
+    if let Some(building) = game_state.service_buildings.iter_mut().find(|b| b.id == building_id) {
+        if building.current_tier_index < building.available_tiers.len() - 1 {
+            let next_tier_index = building.current_tier_index + 1;
+            let upgrade_cost = building.available_tiers[next_tier_index].construction_credits_cost;
+            let next_tier_name = building.available_tiers[next_tier_index].name.clone();
+            let specialist_requirement_next_tier = building.available_tiers[next_tier_index].specialist_requirement;
+
+            if game_state.credits < upgrade_cost as f64 {
+                println!("Not enough credits to upgrade Service Building {} to {}. Required: {}, Available: {:.2}", building_id, next_tier_name, upgrade_cost, game_state.credits);
+            } else {
+                game_state.credits -= upgrade_cost as f64;
+                game_state.ledger.record(TransactionCategory::Upgrade, Some(building_id), -(upgrade_cost as f64));
+                println!("Upgraded Service Building {} to {} for {} credits. Remaining credits: {:.2}", building_id, next_tier_name, upgrade_cost, game_state.credits);
+
+                building.current_tier_index = next_tier_index;
+                if building.assigned_specialists > specialist_requirement_next_tier {
+                    let to_unassign = building.assigned_specialists - specialist_requirement_next_tier;
+                    building.assigned_specialists -= to_unassign;
+                    game_state.assigned_specialists_total = game_state.assigned_specialists_total.saturating_sub(to_unassign);
+                }
+                println!("Service Building {} successfully upgraded to: {}", building_id, next_tier_name);
+                upgraded = true;
+            }
+        } else {
+            println!("Service Building {} is already at max tier.", building_id);
+        }
+    } else {
+        println!("Service Building with ID {} not found.", building_id);
+    }
+
+    if upgraded {
+        update_civic_index(game_state);
+        if let Some(building) = game_state.service_buildings.iter().find(|b| b.id == building_id) {
+            if building.service_type == ServiceType::Education {
+                update_total_specialist_slots(game_state);
+            }
+        }
+    }
+}
+
+pub fn remove_service_building(game_state: &mut GameState, building_id: &str) {
+    if let Some(index) = game_state.service_buildings.iter().position(|b| b.id == building_id) {
+        let removed_building = game_state.service_buildings.remove(index);
+        game_state.assigned_specialists_total = game_state.assigned_specialists_total.saturating_sub(removed_building.assigned_specialists);
+        update_civic_index(game_state);
+        if removed_building.service_type == ServiceType::Education {
+            update_total_specialist_slots(game_state);
+        }
+        println!("Removed Service Building with ID {}", building_id);
+    } else {
+        println!("Service Building with ID {} not found for removal.", building_id);
+    }
+}
+
+pub fn assign_specialists_to_service_building(game_state: &mut GameState, building_id: &str, num_to_assign: u32) {
+    if let Some(building) = game_state.service_buildings.iter_mut().find(|b| b.id == building_id) {
+        if let Some(tier) = building.available_tiers.get(building.current_tier_index) {
+            let available_general_inhabitants = game_state.demographics.working_age.saturating_sub(game_state.assigned_specialists_total);
+            if available_general_inhabitants < num_to_assign {
+                println!(
+                    "Not enough unassigned inhabitants to become specialists for service building {}.",
+                    building_id
+                );
+                return;
+            }
+            if game_state.assigned_specialists_total + num_to_assign
+                > game_state.total_specialist_slots
+            {
+                println!("Not enough specialist accommodation available.");
+                return;
+            }
+            if building.assigned_specialists + num_to_assign > tier.specialist_requirement {
+                println!(
+                    "Cannot assign more specialists than required for service building {}. Max: {}",
+                    building_id, tier.specialist_requirement
+                );
+                return;
+            }
+            building.assigned_specialists += num_to_assign;
+            game_state.assigned_specialists_total += num_to_assign;
+            println!("Assigned {} specialists to service building {}. Total assigned globally: {}", num_to_assign, building_id, game_state.assigned_specialists_total);
+            if building.service_type == ServiceType::Education {
+                update_total_specialist_slots(game_state);
+            }
+        }
+    } else {
+        println!("Service Building {} not found for specialist assignment.", building_id);
+    }
+}
+
+pub fn unassign_specialists_from_service_building(game_state: &mut GameState, building_id: &str, num_to_unassign: u32) {
+    if let Some(building) = game_state.service_buildings.iter_mut().find(|b| b.id == building_id) {
+        let actual_unassign = num_to_unassign.min(building.assigned_specialists);
+        building.assigned_specialists -= actual_unassign;
+        game_state.assigned_specialists_total -= actual_unassign;
+        println!("Unassigned {} specialists from service building {}. Total assigned globally: {}", actual_unassign, building_id, game_state.assigned_specialists_total);
+        if building.service_type == ServiceType::Education {
+            update_total_specialist_slots(game_state);
+        }
+    } else {
+        println!("Service Building {} not found for specialist unassignment.", building_id);
+    }
+}
+
+pub fn get_zone_tiers(zone_type: ZoneType) -> Vec<ZoneTier> {
+    match zone_type {
         ZoneType::Commercial => vec![
             ZoneTier { name: "Small Market Stalls".to_string(), specialist_jobs_provided: 5, civic_index_contribution: 3, upkeep_cost: 10, construction_credits_cost: 100, income_generation: 50, required_tech: None },
             ZoneTier { name: "Shopping Plaza".to_string(), specialist_jobs_provided: 15, civic_index_contribution: 10, upkeep_cost: 30, construction_credits_cost: 300, income_generation: 200, required_tech: Some(Tech::ZoningOrdinances) },
@@ -1339,231 +2833,1420 @@ pub fn get_zone_tiers(zone_type: ZoneType) -> Vec<ZoneTier> {
     }
 }
 
-pub fn add_zone(game_state: &mut GameState, zone_type: ZoneType, tier_index: usize) {
-    let all_tiers = get_zone_tiers(zone_type);
-    if tier_index >= all_tiers.len() {
-        // This case should ideally be prevented by UI, but good to have a log.
-        // No user-facing notification for this internal error.
-        println!("Error: Invalid tier index for zone type {:?}.", zone_type);
-        return;
+pub fn add_zone(game_state: &mut GameState, zone_type: ZoneType, tier_index: usize) {
+    let all_tiers = get_zone_tiers(zone_type);
+    if tier_index >= all_tiers.len() {
+        // This case should ideally be prevented by UI, but good to have a log.
+        // No user-facing notification for this internal error.
+        println!("Error: Invalid tier index for zone type {:?}.", zone_type);
+        return;
+    }
+    let tier_info = &all_tiers[tier_index];
+    if let Some(tech) = tier_info.required_tech {
+        if !game_state.unlocked_techs.contains(&tech) {
+            add_notification(
+                &mut game_state.notifications,
+                format!("Requires {:?} to build {}.", tech, tier_info.name),
+                0.0,
+            );
+            return;
+        }
+    }
+    if game_state.credits < tier_info.construction_credits_cost as f64 {
+        add_notification(&mut game_state.notifications, format!("Not enough credits to build {:?} - {}.", zone_type, tier_info.name), 0.0);
+        return;
+    }
+    let new_id = generate_unique_id();
+    game_state.credits -= tier_info.construction_credits_cost as f64;
+    game_state.ledger.record(TransactionCategory::Construction, Some(&new_id), -(tier_info.construction_credits_cost as f64));
+    add_notification(&mut game_state.notifications, format!("Constructed Zone: {:?} - {}.", zone_type, tier_info.name), 0.0);
+
+    let new_zone = Zone {
+        id: new_id,
+        zone_type,
+        current_tier_index: tier_index,
+        available_tiers: all_tiers.clone(),
+        assigned_specialists: 0,
+        is_active: true,
+        is_mothballed: false,
+        status: BuildingStatus::UnderConstruction,
+        manual_lock: false,
+    };
+    game_state.zones.push(new_zone);
+    update_total_specialist_slots(game_state);
+    update_civic_index(game_state);
+    // Removed "Added Zone..." println as it's covered by "Constructed Zone..."
+}
+
+pub fn upgrade_zone(game_state: &mut GameState, zone_id: &str) {
+    let mut upgraded = false;
+    if let Some(zone) = game_state.zones.iter_mut().find(|z| z.id == zone_id) {
+        if zone.current_tier_index < zone.available_tiers.len() - 1 {
+            let next_tier_index = zone.current_tier_index + 1;
+            let next_tier_info = &zone.available_tiers[next_tier_index];
+            if let Some(tech) = next_tier_info.required_tech.filter(|t| !game_state.unlocked_techs.contains(t)) {
+                add_notification(&mut game_state.notifications, format!("Requires {} to upgrade to {}.", tech, next_tier_info.name), 0.0);
+                return;
+            }
+
+            let upgrade_cost = next_tier_info.construction_credits_cost;
+
+            if game_state.credits < upgrade_cost as f64 {
+                add_notification(&mut game_state.notifications, format!("Not enough credits to upgrade Zone {} to {}. Required: {}, Available: {:.2}", zone_id, next_tier_info.name, upgrade_cost, game_state.credits), 0.0);
+                return;
+            }
+            game_state.credits -= upgrade_cost as f64;
+            game_state.ledger.record(TransactionCategory::Upgrade, Some(zone_id), -(upgrade_cost as f64));
+            // The following println about credits is more of a debug log, notification will be for success.
+            // println!("Upgraded Zone {} to {} for {} credits. Remaining credits: {:.2}", zone_id, next_tier_info.name, upgrade_cost, game_state.credits);
+
+            zone.current_tier_index = next_tier_index;
+            if zone.assigned_specialists > next_tier_info.specialist_jobs_provided {
+                let to_unassign = zone.assigned_specialists - next_tier_info.specialist_jobs_provided;
+                zone.assigned_specialists -= to_unassign;
+                game_state.assigned_specialists_total = game_state.assigned_specialists_total.saturating_sub(to_unassign);
+            }
+            add_notification(&mut game_state.notifications, format!("Zone {} successfully upgraded to: {}.", zone_id, next_tier_info.name), 0.0);
+            upgraded = true;
+        } else {
+            add_notification(&mut game_state.notifications, format!("Zone {} is already at max tier.", zone_id), 0.0);
+        }
+    } else {
+        add_notification(&mut game_state.notifications, format!("Zone with ID {} not found for upgrade.", zone_id), 0.0);
+    }
+
+    if upgraded {
+        update_total_specialist_slots(game_state);
+        update_civic_index(game_state);
+    }
+}
+
+pub fn remove_zone(game_state: &mut GameState, zone_id: &str) {
+    if let Some(index) = game_state.zones.iter().position(|z| z.id == zone_id) {
+        let removed_zone = game_state.zones.remove(index);
+        game_state.assigned_specialists_total = game_state.assigned_specialists_total.saturating_sub(removed_zone.assigned_specialists);
+        update_total_specialist_slots(game_state);
+        update_civic_index(game_state);
+        add_notification(&mut game_state.notifications, format!("Removed Zone with ID {}.", zone_id), 0.0);
+    } else {
+        add_notification(&mut game_state.notifications, format!("Zone with ID {} not found for removal.", zone_id), 0.0);
+    }
+}
+
+pub fn assign_specialists_to_zone(game_state: &mut GameState, zone_id: &str, num_to_assign: u32) {
+    if let Some(zone) = game_state.zones.iter_mut().find(|z| z.id == zone_id) {
+        if let Some(tier) = zone.available_tiers.get(zone.current_tier_index) {
+            let available_general_inhabitants = game_state.demographics.working_age.saturating_sub(game_state.assigned_specialists_total);
+            if available_general_inhabitants < num_to_assign {
+                add_notification(&mut game_state.notifications, format!("Not enough unassigned inhabitants to become specialists for zone {}.", zone_id), 0.0);
+                return;
+            }
+            if game_state.assigned_specialists_total + num_to_assign
+                > game_state.total_specialist_slots
+            {
+                add_notification(
+                    &mut game_state.notifications,
+                    "Not enough specialist accommodation available.".to_string(),
+                    0.0,
+                );
+                return;
+            }
+            if zone.assigned_specialists + num_to_assign > tier.specialist_jobs_provided {
+                add_notification(&mut game_state.notifications, format!("Cannot assign more specialists than available jobs in zone {}. Max: {}", zone_id, tier.specialist_jobs_provided), 0.0);
+                return;
+            }
+            zone.assigned_specialists += num_to_assign;
+            game_state.assigned_specialists_total += num_to_assign;
+            add_notification(&mut game_state.notifications, format!("Assigned {} specialists to zone {}.", num_to_assign, zone_id), 0.0);
+        }
+    } else {
+        add_notification(&mut game_state.notifications, format!("Zone {} not found for specialist assignment.", zone_id), 0.0);
+    }
+}
+
+pub fn unassign_specialists_from_zone(game_state: &mut GameState, zone_id: &str, num_to_unassign: u32) {
+    if let Some(zone) = game_state.zones.iter_mut().find(|z| z.id == zone_id) {
+        let actual_unassign = num_to_unassign.min(zone.assigned_specialists);
+        if actual_unassign > 0 { // Only notify if an actual unassignment happens
+            zone.assigned_specialists -= actual_unassign;
+            game_state.assigned_specialists_total -= actual_unassign;
+            add_notification(&mut game_state.notifications, format!("Unassigned {} specialists from zone {}.", actual_unassign, zone_id), 0.0);
+        }
+        // No notification if trying to unassign from 0, or unassigning 0.
+    } else {
+        add_notification(&mut game_state.notifications, format!("Zone {} not found for specialist unassignment.", zone_id), 0.0);
+    }
+}
+
+pub fn update_total_specialist_slots(game_state: &mut GameState) {
+    let mut total_slots = 0;
+    for structure in &game_state.habitation_structures {
+        if let Some(tier) = structure.available_tiers.get(structure.tier_index) {
+            total_slots += tier.specialist_slots;
+        }
+    }
+    for zone in &game_state.zones {
+        if zone.is_active {
+            if let Some(tier) = zone.available_tiers.get(zone.current_tier_index) {
+                total_slots += tier.specialist_jobs_provided;
+            }
+        }
+    }
+    for building in &game_state.service_buildings {
+        if building.service_type == ServiceType::Education
+            && building.is_active
+            && staffing_fraction(
+                building.assigned_specialists,
+                building.available_tiers[building.current_tier_index].specialist_requirement,
+            ) > 0.0
+        {
+            total_slots += 1;
+        }
+    }
+    game_state.total_specialist_slots = total_slots;
+}
+
+pub struct GameLogicPlugin;
+
+impl Plugin for GameLogicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameState>()
+            .init_resource::<PopulationResource>()
+            .init_resource::<HappinessResource>()
+            .init_resource::<HappinessBreakdown>()
+            .init_resource::<ServiceCoverage>()
+            .init_resource::<ColonyStats>()
+            .init_resource::<GraphData>()
+            .init_resource::<SpatialIndex>()
+            .add_event::<SaveGameEvent>()
+            .add_event::<LoadGameEvent>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    workforce_assignment_system,
+                    modifier_system,
+                    game_tick_system.after(workforce_assignment_system).after(modifier_system),
+                    eureka_system,
+                    research_system.after(eureka_system),
+                    food_consumption_system.after(game_tick_system),
+                    population_growth_system.after(food_consumption_system),
+                    auto_assign_specialists_system.after(population_growth_system),
+                    spaceport_arrivals_system.after(population_growth_system),
+                    housing_assignment_system.after(spaceport_arrivals_system).after(auto_assign_specialists_system),
+                    labor_market_system.after(auto_assign_specialists_system),
+                    fabricator_production_tick_system.after(game_tick_system),
+                    processing_plant_operations_tick_system.after(game_tick_system),
+                    upkeep_income_tick_system.after(processing_plant_operations_tick_system),
+                    close_resource_accounts_system.after(upkeep_income_tick_system).after(fabricator_production_tick_system),
+                    service_coverage_system.after(close_resource_accounts_system).after(housing_assignment_system),
+                    happiness_system.after(service_coverage_system).after(labor_market_system),
+                    update_colony_stats_system.after(happiness_system),
+                    update_graph_data_system.after(update_colony_stats_system),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    agents::sync_citizen_agents_system.after(auto_assign_specialists_system),
+                    agents::assign_citizen_agents_system.after(agents::sync_citizen_agents_system),
+                    agents::citizen_wellbeing_system
+                        .after(agents::assign_citizen_agents_system)
+                        .after(happiness_system)
+                        .before(update_colony_stats_system),
+                ),
+            )
+            .add_systems(Update, (save_game_system, load_game_system));
+    }
+}
+
+// --- Modifiers ---
+
+/// Output of every extractor.
+pub const STAT_EXTRACTOR_OUTPUT: &str = "extractor.output";
+/// Batches per second of every processing plant.
+pub const STAT_PROCESSING_THROUGHPUT: &str = "processing.throughput";
+/// Research progress per tick.
+pub const STAT_RESEARCH_RATE: &str = "research.rate";
+/// Colony-wide happiness, on top of the computed factors.
+pub const STAT_HAPPINESS: &str = "happiness";
+/// Credits per cycle from the legacy structure.
+pub const STAT_LEGACY_INCOME: &str = "income.legacy";
+/// Nutrient paste from every bio-dome.
+pub const STAT_BIO_DOME_OUTPUT: &str = "biodome.output";
+/// Production speed of every fabricator.
+pub const STAT_FABRICATOR_OUTPUT: &str = "fabricator.output";
+/// Credits per cycle from commercial zones.
+pub const STAT_ZONE_INCOME: &str = "income.zones";
+/// Credits per cycle from all taxes together.
+pub const STAT_TAX_INCOME: &str = "income.taxes";
+/// Research output bonus at full Education coverage; partial coverage earns a share of it.
+pub const EDUCATION_RESEARCH_BONUS: f32 = 0.5;
+/// Colony-wide celebration after a research breakthrough.
+const BREAKTHROUGH_HAPPINESS: f32 = 3.0;
+const BREAKTHROUGH_TICKS: u32 = 30;
+
+/// Where a modifier comes from. Techs, service coverage and the legacy structure are standing
+/// effects rebuilt from the colony's state every tick; events are registered once and run out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ModifierSource {
+    Tech(Tech),
+    Service(ServiceType),
+    /// Named after the legacy structure's current tier
+    Legacy(String),
+    Event(String),
+}
+
+impl ModifierSource {
+    pub fn label(&self) -> String {
+        match self {
+            ModifierSource::Tech(tech) => tech.to_string(),
+            ModifierSource::Service(service) => format!("{:?} coverage", service),
+            ModifierSource::Legacy(name) | ModifierSource::Event(name) => name.clone(),
+        }
+    }
+
+    fn is_standing(&self) -> bool {
+        !matches!(self, ModifierSource::Event(_))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ModifierOp {
+    Add(f32),
+    Multiply(f32),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Modifier {
+    pub stat: String,
+    pub source: ModifierSource,
+    pub op: ModifierOp,
+    /// Ticks left before it expires; `None` lasts until its source goes away
+    pub remaining_ticks: Option<u32>,
+}
+
+/// Every active effect on a named stat. Systems read effective values through `apply` rather than
+/// adding bonuses inline: additive modifiers are summed onto the base, then multipliers applied.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModifierStack {
+    pub modifiers: Vec<Modifier>,
+}
+
+impl ModifierStack {
+    /// Adds a modifier, replacing any the same source already has on that stat.
+    pub fn register(&mut self, stat: &str, source: ModifierSource, op: ModifierOp, duration: Option<u32>) {
+        self.modifiers.retain(|m| !(m.stat == stat && m.source == source));
+        self.modifiers.push(Modifier { stat: stat.to_string(), source, op, remaining_ticks: duration });
+    }
+
+    pub fn for_stat<'a>(&'a self, stat: &'a str) -> impl Iterator<Item = &'a Modifier> + 'a {
+        self.modifiers.iter().filter(move |m| m.stat == stat)
+    }
+
+    pub fn apply(&self, stat: &str, base: f32) -> f32 {
+        let (added, multiplier) = self.for_stat(stat).fold((0.0, 1.0), |(added, multiplier), m| match m.op {
+            ModifierOp::Add(value) => (added + value, multiplier),
+            ModifierOp::Multiply(factor) => (added, multiplier * factor),
+        });
+        (base + added) * multiplier
+    }
+
+    /// Effective upkeep of one building in `category`.
+    pub fn upkeep(&self, category: UpkeepCategory, base: u32) -> u32 {
+        self.apply(category.stat(), base as f32).round().max(0.0) as u32
+    }
+
+    /// Counts down timed modifiers and returns the ones that ran out.
+    fn tick(&mut self) -> Vec<Modifier> {
+        let mut expired = Vec::new();
+        self.modifiers.retain_mut(|m| match &mut m.remaining_ticks {
+            Some(0) | Some(1) => {
+                expired.push(m.clone());
+                false
+            }
+            Some(ticks) => {
+                *ticks -= 1;
+                true
+            }
+            None => true,
+        });
+        expired
+    }
+}
+
+/// Readable name of a modifier stat.
+pub fn stat_label(stat: &str) -> &str {
+    match stat {
+        STAT_EXTRACTOR_OUTPUT => "extractor output",
+        STAT_PROCESSING_THROUGHPUT => "processing plant throughput",
+        STAT_RESEARCH_RATE => "research rate",
+        STAT_HAPPINESS => "happiness",
+        STAT_LEGACY_INCOME => "legacy income",
+        STAT_BIO_DOME_OUTPUT => "bio-dome output",
+        STAT_FABRICATOR_OUTPUT => "fabricator output",
+        STAT_ZONE_INCOME => "zone income",
+        STAT_TAX_INCOME => "tax income",
+        other => other,
+    }
+}
+
+/// Rebuilds the standing modifiers from researched techs, service coverage and the legacy structure.
+/// Recomputing them keeps old saves and demolitions in step without bookkeeping at each call site.
+fn refresh_standing_modifiers(game_state: &mut GameState, coverage: &ServiceCoverage) {
+    let stack = &mut game_state.modifiers;
+    stack.modifiers.retain(|m| !m.source.is_standing());
+
+    let mut techs: Vec<Tech> = game_state.unlocked_techs.iter().copied().collect();
+    techs.sort_by_key(|t| t.to_string());
+    for tech in techs {
+        for effect in tech_effects(tech) {
+            if let TechEffect::Modifier { stat, op } = effect {
+                stack.register(stat, ModifierSource::Tech(tech), op, None);
+            }
+        }
+    }
+
+    let education = coverage.coverage.get(&ServiceType::Education).copied().unwrap_or(0.0).min(1.0);
+    if education > 0.0 {
+        stack.register(
+            STAT_RESEARCH_RATE,
+            ModifierSource::Service(ServiceType::Education),
+            ModifierOp::Multiply(1.0 + education * EDUCATION_RESEARCH_BONUS),
+            None,
+        );
+    }
+
+    if let Some(structure) = &game_state.legacy_structure {
+        if let Some(tier) = structure.available_tiers.get(structure.current_tier_index) {
+            let source = ModifierSource::Legacy(tier.name.clone());
+            if tier.happiness_bonus != 0.0 {
+                stack.register(STAT_HAPPINESS, source.clone(), ModifierOp::Add(tier.happiness_bonus), None);
+            }
+            if tier.income_bonus != 0.0 {
+                stack.register(STAT_LEGACY_INCOME, source, ModifierOp::Add(tier.income_bonus as f32), None);
+            }
+        }
+    }
+}
+
+/// Celebrations after a tech is researched lift morale for a while.
+pub fn register_breakthrough(game_state: &mut GameState, tech: Tech) {
+    game_state.modifiers.register(
+        STAT_HAPPINESS,
+        ModifierSource::Event(format!("{} breakthrough", tech)),
+        ModifierOp::Add(BREAKTHROUGH_HAPPINESS),
+        Some(BREAKTHROUGH_TICKS),
+    );
+}
+
+fn modifier_system(mut game_state: ResMut<GameState>, coverage: Res<ServiceCoverage>) {
+    refresh_standing_modifiers(&mut game_state, &coverage);
+    for expired in game_state.modifiers.tick() {
+        add_notification(
+            &mut game_state.notifications,
+            format!("{} has worn off.", expired.source.label()),
+            0.0,
+        );
+    }
+}
+
+// --- Taxation & Budget ---
+
+pub const MAX_INHABITANT_TAX_RATE: f64 = 5.0;
+pub const MAX_SPECIALIST_TAX_RATE: f64 = 10.0;
+pub const MAX_COMMERCIAL_TAX_RATE: f64 = 0.5;
+/// Share of each maximum rate the colony accepts before morale starts to drop.
+const TAX_TOLERANCE: f64 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaxCategory {
+    Inhabitant,
+    Specialist,
+    Commercial,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct TaxRates {
+    /// Credits per general inhabitant per cycle
+    pub inhabitant: f64,
+    /// Credits per assigned specialist per cycle
+    pub specialist: f64,
+    /// Share of commercial zone income collected as tax
+    pub commercial: f64,
+}
+
+impl Default for TaxRates {
+    fn default() -> Self {
+        TaxRates { inhabitant: 1.0, specialist: 2.0, commercial: 0.1 }
+    }
+}
+
+impl TaxRates {
+    pub fn get(&self, category: TaxCategory) -> f64 {
+        match category {
+            TaxCategory::Inhabitant => self.inhabitant,
+            TaxCategory::Specialist => self.specialist,
+            TaxCategory::Commercial => self.commercial,
+        }
     }
-    let tier_info = &all_tiers[tier_index];
-    if let Some(tech) = tier_info.required_tech {
-        if !game_state.unlocked_techs.contains(&tech) {
-            add_notification(
-                &mut game_state.notifications,
-                format!("Requires {:?} to build {}.", tech, tier_info.name),
-                0.0,
-            );
+}
+
+pub fn max_tax_rate(category: TaxCategory) -> f64 {
+    match category {
+        TaxCategory::Inhabitant => MAX_INHABITANT_TAX_RATE,
+        TaxCategory::Specialist => MAX_SPECIALIST_TAX_RATE,
+        TaxCategory::Commercial => MAX_COMMERCIAL_TAX_RATE,
+    }
+}
+
+pub fn tax_rate_step(category: TaxCategory) -> f64 {
+    match category {
+        TaxCategory::Inhabitant => 0.5,
+        TaxCategory::Specialist => 1.0,
+        TaxCategory::Commercial => 0.05,
+    }
+}
+
+pub fn adjust_tax_rate(game_state: &mut GameState, category: TaxCategory, increase: bool) {
+    let step = if increase { tax_rate_step(category) } else { -tax_rate_step(category) };
+    let new_rate = (game_state.tax_rates.get(category) + step).clamp(0.0, max_tax_rate(category));
+    match category {
+        TaxCategory::Inhabitant => game_state.tax_rates.inhabitant = new_rate,
+        TaxCategory::Specialist => game_state.tax_rates.specialist = new_rate,
+        TaxCategory::Commercial => game_state.tax_rates.commercial = new_rate,
+    }
+}
+
+/// Morale lost to taxation; rates up to `TAX_TOLERANCE` of their maximum are free.
+pub fn tax_happiness_penalty(rates: &TaxRates) -> f32 {
+    let excess = |category: TaxCategory, weight: f64| {
+        ((rates.get(category) / max_tax_rate(category)) - TAX_TOLERANCE).max(0.0) * weight
+    };
+    (excess(TaxCategory::Inhabitant, 30.0)
+        + excess(TaxCategory::Specialist, 20.0)
+        + excess(TaxCategory::Commercial, 10.0)) as f32
+}
+
+/// Projected credits per cycle, split by category.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ColonyBudget {
+    pub zone_income: f64,
+    pub legacy_income: f64,
+    pub inhabitant_tax: f64,
+    pub specialist_tax: f64,
+    pub commercial_tax: f64,
+    pub fabricator_upkeep: f64,
+    pub processing_plant_upkeep: f64,
+    pub zone_upkeep: f64,
+    pub service_upkeep: f64,
+    pub loan_payments: f64,
+    pub overdraft_interest: f64,
+}
+
+impl ColonyBudget {
+    pub fn total_income(&self) -> f64 {
+        self.zone_income + self.legacy_income + self.inhabitant_tax + self.specialist_tax + self.commercial_tax
+    }
+
+    pub fn total_upkeep(&self) -> f64 {
+        self.fabricator_upkeep + self.processing_plant_upkeep + self.zone_upkeep + self.service_upkeep
+    }
+
+    pub fn debt_service(&self) -> f64 {
+        self.loan_payments + self.overdraft_interest
+    }
+
+    pub fn net(&self) -> f64 {
+        self.total_income() - self.total_upkeep() - self.debt_service()
+    }
+}
+
+pub fn project_budget(game_state: &GameState) -> ColonyBudget {
+    let mut budget = ColonyBudget::default();
+
+    for zone in game_state.zones.iter().filter(|z| !z.is_mothballed) {
+        if let Some(tier) = zone.available_tiers.get(zone.current_tier_index) {
+            budget.zone_upkeep += game_state.modifiers.upkeep(UpkeepCategory::Zones, tier.upkeep_cost) as f64;
+            if zone.is_active && zone.zone_type == ZoneType::Commercial {
+                budget.zone_income += tier.income_generation as f64;
+                budget.commercial_tax += tier.income_generation as f64 * game_state.tax_rates.commercial;
+            }
+        }
+    }
+
+    budget.zone_income = game_state.modifiers.apply(STAT_ZONE_INCOME, budget.zone_income as f32) as f64;
+    budget.legacy_income = game_state.modifiers.apply(STAT_LEGACY_INCOME, 0.0) as f64;
+
+    let general_inhabitants = game_state.total_inhabitants.saturating_sub(game_state.assigned_specialists_total);
+    budget.inhabitant_tax = general_inhabitants as f64 * game_state.tax_rates.inhabitant;
+    budget.specialist_tax = game_state.assigned_specialists_total as f64 * game_state.tax_rates.specialist;
+    // Tax modifiers apply to the total once, then each line keeps its share of the result.
+    let taxes = budget.inhabitant_tax + budget.specialist_tax + budget.commercial_tax;
+    if taxes > 0.0 {
+        let scale = game_state.modifiers.apply(STAT_TAX_INCOME, taxes as f32) as f64 / taxes;
+        budget.inhabitant_tax *= scale;
+        budget.specialist_tax *= scale;
+        budget.commercial_tax *= scale;
+    }
+
+    for fab in game_state.fabricators.iter().filter(|f| !f.is_mothballed) {
+        if let Some(tier) = fab.available_tiers.get(fab.tier_index) {
+            budget.fabricator_upkeep += game_state.modifiers.upkeep(UpkeepCategory::Fabricators, tier.upkeep_cost) as f64;
+        }
+    }
+    for plant in game_state.processing_plants.iter().filter(|p| !p.is_mothballed) {
+        if let Some(tier) = plant.available_tiers.get(plant.tier_index) {
+            budget.processing_plant_upkeep += game_state.modifiers.upkeep(UpkeepCategory::ProcessingPlants, tier.upkeep_cost) as f64;
+        }
+    }
+    for building in game_state.service_buildings.iter().filter(|b| !b.is_mothballed) {
+        if let Some(tier) = building.available_tiers.get(building.current_tier_index) {
+            budget.service_upkeep += game_state.modifiers.upkeep(UpkeepCategory::ServiceBuildings, tier.upkeep_cost) as f64;
+        }
+    }
+
+    for loan in &game_state.loans {
+        budget.loan_payments += loan.installment.min(loan.balance * (1.0 + loan.interest_rate));
+    }
+    if game_state.credits < 0.0 {
+        budget.overdraft_interest = -game_state.credits * OVERDRAFT_INTEREST_RATE;
+    }
+
+    budget
+}
+
+// --- Upkeep Priority & Mothballing ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UpkeepCategory {
+    Fabricators,
+    ProcessingPlants,
+    Zones,
+    ServiceBuildings,
+}
+
+impl UpkeepCategory {
+    pub fn label(&self) -> &'static str {
+        match self {
+            UpkeepCategory::Fabricators => "Fabricators",
+            UpkeepCategory::ProcessingPlants => "Processing Plants",
+            UpkeepCategory::Zones => "Zones",
+            UpkeepCategory::ServiceBuildings => "Service Buildings",
+        }
+    }
+
+    /// Modifier stat scaling this category's upkeep.
+    pub fn stat(&self) -> &'static str {
+        match self {
+            UpkeepCategory::Fabricators => "upkeep.fabricators",
+            UpkeepCategory::ProcessingPlants => "upkeep.processing_plants",
+            UpkeepCategory::Zones => "upkeep.zones",
+            UpkeepCategory::ServiceBuildings => "upkeep.services",
+        }
+    }
+}
+
+/// Matches the order upkeep was paid in before priorities were configurable.
+pub fn default_upkeep_priority() -> Vec<UpkeepCategory> {
+    vec![
+        UpkeepCategory::Fabricators,
+        UpkeepCategory::ProcessingPlants,
+        UpkeepCategory::Zones,
+        UpkeepCategory::ServiceBuildings,
+    ]
+}
+
+pub fn move_upkeep_priority(game_state: &mut GameState, category: UpkeepCategory, raise: bool) {
+    if let Some(index) = game_state.upkeep_priority.iter().position(|c| *c == category) {
+        if raise && index > 0 {
+            game_state.upkeep_priority.swap(index, index - 1);
+        } else if !raise && index + 1 < game_state.upkeep_priority.len() {
+            game_state.upkeep_priority.swap(index, index + 1);
+        }
+    }
+}
+
+/// Suspends or resumes a building's upkeep and output.
+pub fn toggle_mothball(game_state: &mut GameState, building_id: &str) {
+    let mut result: Option<(String, bool)> = None;
+    let mut civic_or_slots_changed = false;
+
+    if let Some(fab) = game_state.fabricators.iter_mut().find(|f| f.id == building_id) {
+        fab.is_mothballed = !fab.is_mothballed;
+        fab.is_active = false;
+        fab.production_progress_secs = 0.0;
+        if fab.is_mothballed {
+            fab.status = BuildingStatus::Mothballed;
+        }
+        result = Some((format!("Fabricator {}", fab.id), fab.is_mothballed));
+    } else if let Some(plant) = game_state.processing_plants.iter_mut().find(|p| p.id == building_id) {
+        plant.is_mothballed = !plant.is_mothballed;
+        plant.is_active = false;
+        plant.processing_progress = 0.0;
+        if plant.is_mothballed {
+            plant.status = BuildingStatus::Mothballed;
+        }
+        result = Some((format!("Processing Plant {}", plant.id), plant.is_mothballed));
+    } else if let Some(zone) = game_state.zones.iter_mut().find(|z| z.id == building_id) {
+        zone.is_mothballed = !zone.is_mothballed;
+        zone.is_active = !zone.is_mothballed;
+        zone.status = if zone.is_mothballed { BuildingStatus::Mothballed } else { BuildingStatus::Operational };
+        civic_or_slots_changed = true;
+        result = Some((format!("Zone {}", zone.id), zone.is_mothballed));
+    } else if let Some(building) = game_state.service_buildings.iter_mut().find(|b| b.id == building_id) {
+        building.is_mothballed = !building.is_mothballed;
+        building.is_active = !building.is_mothballed;
+        building.status = if building.is_mothballed { BuildingStatus::Mothballed } else { BuildingStatus::Operational };
+        civic_or_slots_changed = true;
+        result = Some((format!("Service Building {}", building.id), building.is_mothballed));
+    }
+
+    match result {
+        Some((name, mothballed)) => {
+            if civic_or_slots_changed {
+                update_civic_index(game_state);
+                update_total_specialist_slots(game_state);
+            }
+            let message = if mothballed {
+                format!("{} mothballed: upkeep and output suspended.", name)
+            } else {
+                format!("{} reactivated.", name)
+            };
+            add_notification(&mut game_state.notifications, message, 0.0);
+        }
+        None => println!("Building with ID {} not found for mothballing.", building_id),
+    }
+}
+
+// --- Resource Bag ---
+
+/// A set of resource amounts that can never go negative. Used for stockpiles, costs and recipes.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "HashMap<ResourceType, f32>", into = "HashMap<ResourceType, f32>")]
+pub struct ResourceBag(HashMap<ResourceType, f32>);
+
+/// Negative or non-finite amounts from a save are read as zero.
+impl From<HashMap<ResourceType, f32>> for ResourceBag {
+    fn from(amounts: HashMap<ResourceType, f32>) -> Self {
+        amounts
+            .into_iter()
+            .map(|(resource, amount)| (resource, if amount.is_finite() { amount.max(0.0) } else { 0.0 }))
+            .collect()
+    }
+}
+
+impl From<ResourceBag> for HashMap<ResourceType, f32> {
+    fn from(bag: ResourceBag) -> Self {
+        bag.0
+    }
+}
+
+impl ResourceBag {
+    pub fn get(&self, resource: ResourceType) -> f32 {
+        self.0.get(&resource).copied().unwrap_or(0.0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&ResourceType, &f32)> {
+        self.0.iter()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &f32> {
+        self.0.values()
+    }
+
+    /// Sum of both bags, or `None` if any amount would overflow.
+    pub fn checked_add(&self, other: &ResourceBag) -> Option<Self> {
+        let mut result = self.clone();
+        for (resource, amount) in other.iter() {
+            let sum = result.get(*resource) + amount;
+            if !sum.is_finite() {
+                return None;
+            }
+            result.0.insert(*resource, sum);
+        }
+        Some(result)
+    }
+
+    /// Difference of both bags, or `None` if any amount would go negative.
+    pub fn checked_sub(&self, other: &ResourceBag) -> Option<Self> {
+        let mut result = self.clone();
+        for (resource, amount) in other.iter() {
+            let difference = result.get(*resource) - amount;
+            if difference < 0.0 {
+                return None;
+            }
+            result.0.insert(*resource, difference);
+        }
+        Some(result)
+    }
+
+    pub fn can_afford(&self, cost: &ResourceBag) -> bool {
+        cost.iter().all(|(resource, amount)| self.get(*resource) >= *amount)
+    }
+
+    /// Amounts of `cost` not covered by this bag.
+    pub fn shortfall(&self, cost: &ResourceBag) -> ResourceBag {
+        cost.iter()
+            .filter(|(resource, amount)| self.get(**resource) < **amount)
+            .map(|(resource, amount)| (*resource, amount - self.get(*resource)))
+            .collect()
+    }
+
+    /// Removes all of `cost` or nothing. On failure returns what was missing.
+    pub fn try_withdraw(&mut self, cost: &ResourceBag) -> Result<(), ResourceBag> {
+        match self.checked_sub(cost) {
+            Some(remaining) => {
+                *self = remaining;
+                Ok(())
+            }
+            None => Err(self.shortfall(cost)),
+        }
+    }
+
+    /// Removes as much of `amount` as is available and returns what was removed.
+    pub fn withdraw_up_to(&mut self, resource: ResourceType, amount: f32) -> f32 {
+        let entry = self.0.entry(resource).or_insert(0.0);
+        let removed = amount.max(0.0).min(*entry);
+        *entry -= removed;
+        removed
+    }
+
+    /// Adds `amount` without exceeding `capacity` and returns the overflow that did not fit.
+    /// Nothing is added if the sum would overflow.
+    pub fn deposit(&mut self, resource: ResourceType, amount: f32, capacity: f32) -> f32 {
+        let amount = amount.max(0.0);
+        let added = (capacity - self.get(resource)).clamp(0.0, amount);
+        match self.checked_add(&ResourceBag::from([(resource, added)])) {
+            Some(sum) => {
+                *self = sum;
+                amount - added
+            }
+            None => amount,
+        }
+    }
+}
+
+impl FromIterator<(ResourceType, f32)> for ResourceBag {
+    fn from_iter<I: IntoIterator<Item = (ResourceType, f32)>>(iter: I) -> Self {
+        ResourceBag(iter.into_iter().map(|(resource, amount)| (resource, amount.max(0.0))).collect())
+    }
+}
+
+impl<const N: usize> From<[(ResourceType, f32); N]> for ResourceBag {
+    fn from(pairs: [(ResourceType, f32); N]) -> Self {
+        pairs.into_iter().collect()
+    }
+}
+
+impl<'a> IntoIterator for &'a ResourceBag {
+    type Item = (&'a ResourceType, &'a f32);
+    type IntoIter = std::collections::hash_map::Iter<'a, ResourceType, f32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+// --- Resource Accounting ---
+
+/// Number of completed ticks averaged when reporting flow rates.
+const RESOURCE_FLOW_WINDOW: usize = 10;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceFlow {
+    pub produced: f32,
+    pub consumed: f32,
+}
+
+impl ResourceFlow {
+    pub fn net(&self) -> f32 {
+        self.produced - self.consumed
+    }
+}
+
+/// Records every change to `current_resources`, per resource and per source.
+/// Stock should only be modified through `produce` and `consume`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceAccounts {
+    current: HashMap<ResourceType, ResourceFlow>,
+    current_by_source: HashMap<String, HashMap<ResourceType, ResourceFlow>>,
+    /// Completed ticks, newest first
+    history: VecDeque<HashMap<ResourceType, ResourceFlow>>,
+    /// Per-source flows of the last completed tick
+    pub last_tick_by_source: HashMap<String, HashMap<ResourceType, ResourceFlow>>,
+}
+
+impl ResourceAccounts {
+    /// Adds up to `amount` of `resource`, never exceeding `capacity`. Returns the amount actually added.
+    pub fn produce(
+        &mut self,
+        stock: &mut ResourceBag,
+        resource: ResourceType,
+        amount: f32,
+        capacity: f32,
+        source: &str,
+    ) -> f32 {
+        let added = amount.max(0.0) - stock.deposit(resource, amount, capacity);
+        self.current.entry(resource).or_default().produced += added;
+        self.current_by_source
+            .entry(source.to_string())
+            .or_default()
+            .entry(resource)
+            .or_default()
+            .produced += added;
+        added
+    }
+
+    /// Removes up to `amount` of `resource`, never going below zero. Returns the amount actually removed.
+    pub fn consume(
+        &mut self,
+        stock: &mut ResourceBag,
+        resource: ResourceType,
+        amount: f32,
+        source: &str,
+    ) -> f32 {
+        let removed = stock.withdraw_up_to(resource, amount);
+        self.record_consumption(resource, removed, source);
+        removed
+    }
+
+    /// Withdraws all of `cost` or nothing, recording the consumption on success.
+    pub fn withdraw(&mut self, stock: &mut ResourceBag, cost: &ResourceBag, source: &str) -> Result<(), ResourceBag> {
+        stock.try_withdraw(cost)?;
+        for (resource, amount) in cost {
+            self.record_consumption(*resource, *amount, source);
+        }
+        Ok(())
+    }
+
+    /// Deposits every amount in `bag` up to `capacity`, returning the overflow.
+    pub fn deposit(&mut self, stock: &mut ResourceBag, bag: &ResourceBag, capacity: f32, source: &str) -> ResourceBag {
+        bag.iter()
+            .map(|(resource, amount)| (*resource, amount - self.produce(stock, *resource, *amount, capacity, source)))
+            .filter(|(_, overflow)| *overflow > 0.0)
+            .collect()
+    }
+
+    /// Trims stored goods back to `capacity`, e.g. after a silo is removed, booking the loss as storage overflow.
+    /// Power is held by the grid rather than silos and is left alone.
+    pub fn enforce_capacity(&mut self, stock: &mut ResourceBag, capacity: f32) {
+        let excess: Vec<(ResourceType, f32)> = stock
+            .iter()
+            .filter(|(resource, amount)| **resource != ResourceType::Power && **amount > capacity)
+            .map(|(resource, amount)| (*resource, amount - capacity))
+            .collect();
+        for (resource, amount) in excess {
+            self.consume(stock, resource, amount, "Storage overflow");
+        }
+    }
+
+    fn record_consumption(&mut self, resource: ResourceType, amount: f32, source: &str) {
+        self.current.entry(resource).or_default().consumed += amount;
+        self.current_by_source
+            .entry(source.to_string())
+            .or_default()
+            .entry(resource)
+            .or_default()
+            .consumed += amount;
+    }
+
+    /// Closes the current tick and starts recording the next one.
+    pub fn end_tick(&mut self) {
+        self.history.push_front(std::mem::take(&mut self.current));
+        if self.history.len() > RESOURCE_FLOW_WINDOW {
+            self.history.pop_back();
+        }
+        self.last_tick_by_source = std::mem::take(&mut self.current_by_source);
+    }
+
+    /// Average production and consumption per tick over the recent window.
+    pub fn rate(&self, resource: ResourceType) -> ResourceFlow {
+        if self.history.is_empty() {
+            return ResourceFlow::default();
+        }
+        let mut total = ResourceFlow::default();
+        for tick in &self.history {
+            if let Some(flow) = tick.get(&resource) {
+                total.produced += flow.produced;
+                total.consumed += flow.consumed;
+            }
+        }
+        let ticks = self.history.len() as f32;
+        ResourceFlow { produced: total.produced / ticks, consumed: total.consumed / ticks }
+    }
+
+    /// Ticks until `resource` runs out at the current rate, or `None` if it is not draining.
+    pub fn ticks_to_depletion(&self, resource: ResourceType, stock: f32) -> Option<f32> {
+        let net = self.rate(resource).net();
+        if net < 0.0 {
+            Some(stock.max(0.0) / -net)
+        } else {
+            None
+        }
+    }
+}
+
+// --- Financial Ledger ---
+
+pub const LEGACY_STRUCTURE_ID: &str = "legacy_structure";
+pub const ADMINISTRATIVE_SPIRE_ID: &str = "administrative_spire";
+const MAX_LEDGER_TRANSACTIONS: usize = 2000;
+const MAX_LEDGER_HISTORY: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TransactionCategory {
+    Construction,
+    Upgrade,
+    Upkeep,
+    ZoneIncome,
+    LegacyIncome,
+    Taxes,
+    Research,
+    Loan,
+    DebtService,
+    Liquidation,
+}
+
+impl TransactionCategory {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TransactionCategory::Construction => "Construction",
+            TransactionCategory::Upgrade => "Upgrades",
+            TransactionCategory::Upkeep => "Upkeep",
+            TransactionCategory::ZoneIncome => "Zone Income",
+            TransactionCategory::LegacyIncome => "Legacy Income",
+            TransactionCategory::Taxes => "Taxes",
+            TransactionCategory::Research => "Research",
+            TransactionCategory::Loan => "Loans",
+            TransactionCategory::DebtService => "Debt Service",
+            TransactionCategory::Liquidation => "Liquidation",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+    pub tick: u64,
+    pub category: TransactionCategory,
+    pub source_id: Option<String>,
+    /// Positive for income, negative for expenses
+    pub amount: f64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerTickSummary {
+    pub tick: u64,
+    pub income: f64,
+    pub expenses: f64,
+    /// Expenses by source ID, or by category label for expenses without a source
+    #[serde(default)]
+    pub cost_centers: HashMap<String, f64>,
+}
+
+/// Rolling record of every credit movement. Per-cycle totals are kept apart from the capped
+/// transaction log, so a busy cycle can't push its own rows out of the summaries.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ledger {
+    pub current_tick: u64,
+    /// Raw transactions, newest first
+    pub transactions: VecDeque<Transaction>,
+    /// Totals of the cycle still in progress
+    #[serde(default)]
+    pub current: LedgerTickSummary,
+    /// Totals of completed cycles, oldest first
+    pub history: VecDeque<LedgerTickSummary>,
+}
+
+impl Ledger {
+    pub fn record(&mut self, category: TransactionCategory, source_id: Option<&str>, amount: f64) {
+        if amount == 0.0 {
             return;
         }
+        if amount > 0.0 {
+            self.current.income += amount;
+        } else {
+            self.current.expenses -= amount;
+            let key = source_id.map_or_else(|| category.label().to_string(), str::to_string);
+            *self.current.cost_centers.entry(key).or_insert(0.0) -= amount;
+        }
+        self.transactions.push_front(Transaction {
+            tick: self.current_tick,
+            category,
+            source_id: source_id.map(str::to_string),
+            amount,
+        });
+        if self.transactions.len() > MAX_LEDGER_TRANSACTIONS {
+            self.transactions.pop_back();
+        }
     }
-    if game_state.credits < tier_info.construction_credits_cost as f64 {
-        add_notification(&mut game_state.notifications, format!("Not enough credits to build {:?} - {}.", zone_type, tier_info.name), 0.0);
-        return;
+
+    /// Closes the current cycle into the history and starts the next one.
+    pub fn advance_tick(&mut self) {
+        let mut summary = std::mem::take(&mut self.current);
+        summary.tick = self.current_tick;
+        self.history.push_back(summary);
+        if self.history.len() > MAX_LEDGER_HISTORY {
+            self.history.pop_front();
+        }
+        self.current_tick += 1;
     }
-    game_state.credits -= tier_info.construction_credits_cost as f64;
-    add_notification(&mut game_state.notifications, format!("Constructed Zone: {:?} - {}.", zone_type, tier_info.name), 0.0);
 
-    let new_zone = Zone {
-        id: generate_unique_id(),
-        zone_type,
-        current_tier_index: tier_index,
-        available_tiers: all_tiers.clone(),
-        assigned_specialists: 0,
-        is_active: true,
-    };
-    game_state.zones.push(new_zone);
-    update_total_specialist_slots(game_state);
-    update_civic_index(game_state);
-    // Removed "Added Zone..." println as it's covered by "Constructed Zone..."
+    /// Largest spenders across the retained history and the cycle in progress.
+    /// Expenses without a source are grouped by category.
+    pub fn top_cost_centers(&self, count: usize) -> Vec<(String, f64)> {
+        let mut totals: HashMap<String, f64> = HashMap::new();
+        for summary in self.history.iter().chain(std::iter::once(&self.current)) {
+            for (source, amount) in &summary.cost_centers {
+                *totals.entry(source.clone()).or_insert(0.0) += amount;
+            }
+        }
+        let mut centers: Vec<(String, f64)> = totals.into_iter().collect();
+        centers.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        centers.truncate(count);
+        centers
+    }
+}
+
+/// Human-readable name for a ledger source ID.
+pub fn describe_ledger_source(game_state: &GameState, source_id: &str) -> String {
+    if source_id == LEGACY_STRUCTURE_ID {
+        return "Legacy Structure".to_string();
+    }
+    if source_id == ADMINISTRATIVE_SPIRE_ID {
+        return "Administrative Spire".to_string();
+    }
+    if let Some(fab) = game_state.fabricators.iter().find(|f| f.id == source_id) {
+        if let Some(tier) = fab.available_tiers.get(fab.tier_index) {
+            return format!("{} ({})", tier.name, fab.id);
+        }
+    }
+    if let Some(plant) = game_state.processing_plants.iter().find(|p| p.id == source_id) {
+        if let Some(tier) = plant.available_tiers.get(plant.tier_index) {
+            return format!("{} ({})", tier.name, plant.id);
+        }
+    }
+    if let Some(zone) = game_state.zones.iter().find(|z| z.id == source_id) {
+        if let Some(tier) = zone.available_tiers.get(zone.current_tier_index) {
+            return format!("{} ({})", tier.name, zone.id);
+        }
+    }
+    if let Some(building) = game_state.service_buildings.iter().find(|b| b.id == source_id) {
+        if let Some(tier) = building.available_tiers.get(building.current_tier_index) {
+            return format!("{} ({})", tier.name, building.id);
+        }
+    }
+    if let Some(structure) = game_state.habitation_structures.iter().find(|h| h.id == source_id) {
+        if let Some(tier) = structure.available_tiers.get(structure.tier_index) {
+            return format!("{} ({})", tier.name, structure.id);
+        }
+    }
+    if let Some(loan) = game_state.loans.iter().find(|l| l.id == source_id) {
+        return loan.name.clone();
+    }
+    source_id.to_string()
 }
 
-pub fn upgrade_zone(game_state: &mut GameState, zone_id: &str) {
-    let mut upgraded = false;
-    if let Some(zone) = game_state.zones.iter_mut().find(|z| z.id == zone_id) {
-        if zone.current_tier_index < zone.available_tiers.len() - 1 {
-            let next_tier_index = zone.current_tier_index + 1;
-            let next_tier_info = &zone.available_tiers[next_tier_index];
-
-            let upgrade_cost = next_tier_info.construction_credits_cost;
+// --- Debt & Bankruptcy ---
+
+/// How far below zero the treasury may go before obligations go unpaid.
+pub const CREDIT_LIMIT: f64 = 5000.0;
+pub const OVERDRAFT_INTEREST_RATE: f64 = 0.005;
+pub const MAX_ACTIVE_LOANS: usize = 3;
+const RECEIVERSHIP_CYCLES: u32 = 10;
+const FORECLOSURE_CYCLES: u32 = 30;
+/// Credits per unit the off-world bank pays when liquidating stockpiles.
+const LIQUIDATION_PRICE: f32 = 0.5;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FinancialStatus {
+    #[default]
+    Solvent,
+    /// Negative balance, still within the credit limit
+    Overdrawn,
+    /// Upkeep or loan payments went unpaid; cleared once the balance is back above zero
+    Bankrupt,
+}
 
-            if game_state.credits < upgrade_cost as f64 {
-                add_notification(&mut game_state.notifications, format!("Not enough credits to upgrade Zone {} to {}. Required: {}, Available: {:.2}", zone_id, next_tier_info.name, upgrade_cost, game_state.credits), 0.0);
-                return;
-            }
-            game_state.credits -= upgrade_cost as f64;
-            // The following println about credits is more of a debug log, notification will be for success.
-            // println!("Upgraded Zone {} to {} for {} credits. Remaining credits: {:.2}", zone_id, next_tier_info.name, upgrade_cost, game_state.credits);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BankruptcyStage {
+    Notice,
+    Receivership,
+    Foreclosure,
+}
 
-            zone.current_tier_index = next_tier_index;
-            if zone.assigned_specialists > next_tier_info.specialist_jobs_provided {
-                let to_unassign = zone.assigned_specialists - next_tier_info.specialist_jobs_provided;
-                zone.assigned_specialists -= to_unassign;
-                game_state.assigned_specialists_total = game_state.assigned_specialists_total.saturating_sub(to_unassign);
-            }
-            add_notification(&mut game_state.notifications, format!("Zone {} successfully upgraded to: {}.", zone_id, next_tier_info.name), 0.0);
-            upgraded = true;
+impl BankruptcyStage {
+    pub fn from_cycles(cycles: u32) -> Self {
+        if cycles >= FORECLOSURE_CYCLES {
+            BankruptcyStage::Foreclosure
+        } else if cycles >= RECEIVERSHIP_CYCLES {
+            BankruptcyStage::Receivership
         } else {
-            add_notification(&mut game_state.notifications, format!("Zone {} is already at max tier.", zone_id), 0.0);
+            BankruptcyStage::Notice
         }
-    } else {
-        add_notification(&mut game_state.notifications, format!("Zone with ID {} not found for upgrade.", zone_id), 0.0);
     }
 
-    if upgraded {
-        update_total_specialist_slots(game_state);
-        update_civic_index(game_state);
+    pub fn happiness_penalty(&self) -> f32 {
+        match self {
+            BankruptcyStage::Notice => 10.0,
+            BankruptcyStage::Receivership => 20.0,
+            BankruptcyStage::Foreclosure => 30.0,
+        }
+    }
+
+    /// Share of each stockpile the bank liquidates per cycle.
+    fn liquidation_share(&self) -> f32 {
+        match self {
+            BankruptcyStage::Notice => 0.0,
+            BankruptcyStage::Receivership => 0.1,
+            BankruptcyStage::Foreclosure => 0.25,
+        }
     }
 }
 
-pub fn remove_zone(game_state: &mut GameState, zone_id: &str) {
-    if let Some(index) = game_state.zones.iter().position(|z| z.id == zone_id) {
-        let removed_zone = game_state.zones.remove(index);
-        game_state.assigned_specialists_total = game_state.assigned_specialists_total.saturating_sub(removed_zone.assigned_specialists);
-        update_total_specialist_slots(game_state);
-        update_civic_index(game_state);
-        add_notification(&mut game_state.notifications, format!("Removed Zone with ID {}.", zone_id), 0.0);
+pub fn bankruptcy_happiness_penalty(game_state: &GameState) -> f32 {
+    if game_state.financial_status == FinancialStatus::Bankrupt {
+        BankruptcyStage::from_cycles(game_state.bankruptcy_cycles).happiness_penalty()
     } else {
-        add_notification(&mut game_state.notifications, format!("Zone with ID {} not found for removal.", zone_id), 0.0);
+        0.0
     }
 }
 
-pub fn assign_specialists_to_zone(game_state: &mut GameState, zone_id: &str, num_to_assign: u32) {
-    if let Some(zone) = game_state.zones.iter_mut().find(|z| z.id == zone_id) {
-        if let Some(tier) = zone.available_tiers.get(zone.current_tier_index) {
-            let available_general_inhabitants = game_state.total_inhabitants.saturating_sub(game_state.assigned_specialists_total);
-            if available_general_inhabitants < num_to_assign {
-                add_notification(&mut game_state.notifications, format!("Not enough unassigned inhabitants to become specialists for zone {}.", zone_id), 0.0);
-                return;
-            }
-            if game_state.assigned_specialists_total + num_to_assign
-                > game_state.total_specialist_slots
-            {
-                add_notification(
-                    &mut game_state.notifications,
-                    "Not enough specialist accommodation available.".to_string(),
-                    0.0,
-                );
-                return;
-            }
-            if zone.assigned_specialists + num_to_assign > tier.specialist_jobs_provided {
-                add_notification(&mut game_state.notifications, format!("Cannot assign more specialists than available jobs in zone {}. Max: {}", zone_id, tier.specialist_jobs_provided), 0.0);
-                return;
-            }
-            zone.assigned_specialists += num_to_assign;
-            game_state.assigned_specialists_total += num_to_assign;
-            add_notification(&mut game_state.notifications, format!("Assigned {} specialists to zone {}.", num_to_assign, zone_id), 0.0);
-        }
+#[derive(Clone, Debug)]
+pub struct LoanOffer {
+    pub name: String,
+    pub amount: f64,
+    /// Interest per cycle
+    pub interest_rate: f64,
+    pub term_cycles: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Loan {
+    pub id: String,
+    pub name: String,
+    pub principal: f64,
+    pub balance: f64,
+    pub interest_rate: f64,
+    pub installment: f64,
+}
+
+pub fn get_loan_offers() -> Vec<LoanOffer> {
+    vec![
+        LoanOffer { name: "Bridge Loan".to_string(), amount: 2000.0, interest_rate: 0.01, term_cycles: 50 },
+        LoanOffer { name: "Development Loan".to_string(), amount: 10000.0, interest_rate: 0.015, term_cycles: 100 },
+        LoanOffer { name: "Expansion Bond".to_string(), amount: 50000.0, interest_rate: 0.02, term_cycles: 200 },
+    ]
+}
+
+fn loan_installment(offer: &LoanOffer) -> f64 {
+    let r = offer.interest_rate;
+    let n = offer.term_cycles as i32;
+    if r == 0.0 {
+        offer.amount / n as f64
     } else {
-        add_notification(&mut game_state.notifications, format!("Zone {} not found for specialist assignment.", zone_id), 0.0);
+        offer.amount * r / (1.0 - (1.0 + r).powi(-n))
     }
 }
 
-pub fn unassign_specialists_from_zone(game_state: &mut GameState, zone_id: &str, num_to_unassign: u32) {
-    if let Some(zone) = game_state.zones.iter_mut().find(|z| z.id == zone_id) {
-        let actual_unassign = num_to_unassign.min(zone.assigned_specialists);
-        if actual_unassign > 0 { // Only notify if an actual unassignment happens
-            zone.assigned_specialists -= actual_unassign;
-            game_state.assigned_specialists_total -= actual_unassign;
-            add_notification(&mut game_state.notifications, format!("Unassigned {} specialists from zone {}.", actual_unassign, zone_id), 0.0);
-        }
-        // No notification if trying to unassign from 0, or unassigning 0.
-    } else {
-        add_notification(&mut game_state.notifications, format!("Zone {} not found for specialist unassignment.", zone_id), 0.0);
+pub fn take_loan(game_state: &mut GameState, offer_index: usize) {
+    let offers = get_loan_offers();
+    let Some(offer) = offers.get(offer_index) else {
+        println!("Error: Invalid loan offer index.");
+        return;
+    };
+    if game_state.financial_status == FinancialStatus::Bankrupt {
+        add_notification(&mut game_state.notifications, "The off-world bank refuses to lend to a bankrupt colony.".to_string(), 0.0);
+        return;
+    }
+    if game_state.loans.len() >= MAX_ACTIVE_LOANS {
+        add_notification(&mut game_state.notifications, format!("Loan refused: at most {} loans may be outstanding.", MAX_ACTIVE_LOANS), 0.0);
+        return;
     }
+    let loan_id = generate_unique_id();
+    game_state.credits += offer.amount;
+    game_state.ledger.record(TransactionCategory::Loan, Some(&loan_id), offer.amount);
+    game_state.loans.push(Loan {
+        id: loan_id,
+        name: offer.name.clone(),
+        principal: offer.amount,
+        balance: offer.amount,
+        interest_rate: offer.interest_rate,
+        installment: loan_installment(offer),
+    });
+    add_notification(
+        &mut game_state.notifications,
+        format!("{} of {:.0} Cr received at {:.1}% per cycle.", offer.name, offer.amount, offer.interest_rate * 100.0),
+        0.0,
+    );
 }
 
-pub fn update_total_specialist_slots(game_state: &mut GameState) {
-    let mut total_slots = 0;
-    for structure in &game_state.habitation_structures {
-        if let Some(tier) = structure.available_tiers.get(structure.tier_index) {
-            total_slots += tier.specialist_slots;
+pub fn repay_loan(game_state: &mut GameState, loan_id: &str) {
+    let Some(index) = game_state.loans.iter().position(|l| l.id == loan_id) else {
+        println!("Loan with ID {} not found.", loan_id);
+        return;
+    };
+    let balance = game_state.loans[index].balance;
+    if game_state.credits < balance {
+        add_notification(&mut game_state.notifications, format!("Not enough credits to repay {} ({:.0} Cr).", game_state.loans[index].name, balance), 0.0);
+        return;
+    }
+    game_state.credits -= balance;
+    game_state.ledger.record(TransactionCategory::DebtService, Some(loan_id), -balance);
+    let loan = game_state.loans.remove(index);
+    add_notification(&mut game_state.notifications, format!("{} repaid in full.", loan.name), 0.0);
+}
+
+/// Accrues interest and collects installments. Returns false if any payment was missed.
+fn service_debt_system(game_state: &mut GameState) -> bool {
+    let mut all_paid = true;
+
+    if game_state.credits < 0.0 {
+        let interest = -game_state.credits * OVERDRAFT_INTEREST_RATE;
+        game_state.credits -= interest;
+        game_state.ledger.record(TransactionCategory::DebtService, None, -interest);
+        if game_state.credits < -CREDIT_LIMIT {
+            all_paid = false;
+            add_notification(
+                &mut game_state.notifications,
+                format!("ALERT: Overdraft interest pushed the treasury past the {:.0} Cr credit limit.", CREDIT_LIMIT),
+                0.0,
+            );
         }
     }
-    for zone in &game_state.zones {
-        if zone.is_active {
-            if let Some(tier) = zone.available_tiers.get(zone.current_tier_index) {
-                total_slots += tier.specialist_jobs_provided;
+
+    let mut repaid = Vec::new();
+    for loan in game_state.loans.iter_mut() {
+        loan.balance += loan.balance * loan.interest_rate;
+        let payment = loan.installment.min(loan.balance);
+        if game_state.credits - payment >= -CREDIT_LIMIT {
+            game_state.credits -= payment;
+            game_state.ledger.record(TransactionCategory::DebtService, Some(&loan.id), -payment);
+            loan.balance -= payment;
+            if loan.balance <= 0.01 {
+                repaid.push(loan.id.clone());
             }
+        } else {
+            all_paid = false;
+            add_notification(&mut game_state.notifications, format!("ALERT: Missed payment on {}.", loan.name), 0.0);
         }
     }
-    for building in &game_state.service_buildings {
-        if building.service_type == ServiceType::Education
-            && building.is_active
-            && building.assigned_specialists
-                >= building.available_tiers[building.current_tier_index].specialist_requirement
-        {
-            total_slots += 1;
+    for id in repaid {
+        if let Some(index) = game_state.loans.iter().position(|l| l.id == id) {
+            let loan = game_state.loans.remove(index);
+            add_notification(&mut game_state.notifications, format!("{} fully repaid.", loan.name), 0.0);
         }
     }
-    game_state.total_specialist_slots = total_slots;
-}
 
-pub struct GameLogicPlugin;
+    all_paid
+}
 
-impl Plugin for GameLogicPlugin {
-    fn build(&self, app: &mut App) {
-        app.init_resource::<GameState>()
-            .init_resource::<PopulationResource>()
-            .init_resource::<HappinessResource>()
-            .init_resource::<ServiceCoverage>()
-            .init_resource::<ColonyStats>()
-            .init_resource::<GraphData>()
-            .add_event::<SaveGameEvent>()
-            .add_event::<LoadGameEvent>()
-            .add_systems(
-                FixedUpdate,
-                (
-                    workforce_assignment_system,
-                    game_tick_system.after(workforce_assignment_system),
-                    research_system,
-                    food_consumption_system.after(game_tick_system),
-                    population_growth_system.after(food_consumption_system),
-                    fabricator_production_tick_system.after(game_tick_system),
-                    processing_plant_operations_tick_system.after(game_tick_system),
-                    upkeep_income_tick_system.after(processing_plant_operations_tick_system),
-                    clamp_resource_system.after(upkeep_income_tick_system),
-                    service_coverage_system.after(clamp_resource_system),
-                    happiness_system.after(service_coverage_system),
-                    update_colony_stats_system.after(happiness_system),
-                    update_graph_data_system.after(update_colony_stats_system),
-                ),
-            )
-            .add_systems(Update, (save_game_system, load_game_system));
+fn update_financial_status(game_state: &mut GameState, obligations_met: bool) {
+    if !obligations_met && game_state.financial_status != FinancialStatus::Bankrupt {
+        game_state.financial_status = FinancialStatus::Bankrupt;
+        game_state.bankruptcy_cycles = 0;
+        add_notification(
+            &mut game_state.notifications,
+            "ALERT: Colony declared bankrupt. Loans frozen; restore a positive balance to recover.".to_string(),
+            0.0,
+        );
+        return;
     }
-}
 
-fn generate_income_system(game_state: &mut GameState) {
-    let mut total_income_this_period = 0.0;
+    if game_state.financial_status == FinancialStatus::Bankrupt {
+        if game_state.credits >= 0.0 {
+            game_state.financial_status = FinancialStatus::Solvent;
+            game_state.bankruptcy_cycles = 0;
+            add_notification(&mut game_state.notifications, "Colony has emerged from bankruptcy.".to_string(), 0.0);
+            return;
+        }
 
-    for zone in &game_state.zones {
-        if zone.is_active && zone.zone_type == ZoneType::Commercial {
-            if let Some(tier) = zone.available_tiers.get(zone.current_tier_index) {
-                total_income_this_period += tier.income_generation as f64;
+        let previous_stage = BankruptcyStage::from_cycles(game_state.bankruptcy_cycles);
+        game_state.bankruptcy_cycles += 1;
+        let stage = BankruptcyStage::from_cycles(game_state.bankruptcy_cycles);
+        if stage != previous_stage {
+            let message = match stage {
+                BankruptcyStage::Receivership => "ALERT: Colony placed in receivership. The bank is liquidating stockpiles.",
+                BankruptcyStage::Foreclosure => "ALERT: Foreclosure proceedings begun. Liquidation accelerated.",
+                BankruptcyStage::Notice => "ALERT: Colony bankrupt.",
+            };
+            add_notification(&mut game_state.notifications, message.to_string(), 0.0);
+        }
+
+        let share = stage.liquidation_share();
+        if share > 0.0 {
+            let mut proceeds = 0.0;
+            let holdings: Vec<(ResourceType, f32)> = game_state
+                .current_resources
+                .iter()
+                .filter(|(resource, amount)| **resource != ResourceType::Power && **amount > 0.0)
+                .map(|(resource, amount)| (*resource, *amount))
+                .collect();
+            for (resource, amount) in holdings {
+                let seized = game_state.resource_accounts.consume(&mut game_state.current_resources, resource, amount * share, "Liquidation");
+                proceeds += seized * LIQUIDATION_PRICE;
             }
+            game_state.credits += proceeds as f64;
+            game_state.ledger.record(TransactionCategory::Liquidation, None, proceeds as f64);
         }
+        return;
     }
 
-    if let Some(structure) = &game_state.legacy_structure {
-        if let Some(tier) = structure.available_tiers.get(structure.current_tier_index) {
-            total_income_this_period += tier.income_bonus;
-        }
+    let new_status = if game_state.credits < 0.0 { FinancialStatus::Overdrawn } else { FinancialStatus::Solvent };
+    if new_status != game_state.financial_status {
+        let message = match new_status {
+            FinancialStatus::Overdrawn => format!("ALERT: Treasury overdrawn. Credit limit: {:.0} Cr.", CREDIT_LIMIT),
+            _ => "Treasury balance restored.".to_string(),
+        };
+        add_notification(&mut game_state.notifications, message, 0.0);
+        game_state.financial_status = new_status;
     }
+}
+
+fn generate_income_system(game_state: &mut GameState) {
+    let budget = project_budget(game_state);
+    let total_income_this_period = budget.total_income();
 
     if total_income_this_period > 0.0 {
         game_state.credits += total_income_this_period;
+
+        for zone in game_state.zones.iter().filter(|z| z.is_active && !z.is_mothballed && z.zone_type == ZoneType::Commercial) {
+            if let Some(tier) = zone.available_tiers.get(zone.current_tier_index) {
+                game_state.ledger.record(TransactionCategory::ZoneIncome, Some(&zone.id), tier.income_generation as f64);
+            }
+        }
+        if budget.legacy_income > 0.0 {
+            game_state.ledger.record(TransactionCategory::LegacyIncome, Some(LEGACY_STRUCTURE_ID), budget.legacy_income);
+        }
+        let taxes = budget.inhabitant_tax + budget.specialist_tax + budget.commercial_tax;
+        if taxes > 0.0 {
+            game_state.ledger.record(TransactionCategory::Taxes, None, taxes);
+        }
     }
 }
 
-fn deduct_upkeep_system(game_state: &mut GameState) {
+/// Pays building upkeep in the player's category priority order, drawing on the credit limit if needed.
+/// Returns false if any upkeep went unpaid.
+fn deduct_upkeep_system(game_state: &mut GameState) -> bool {
     let mut remaining_credits = game_state.credits;
     let mut civic_index_needs_update = false;
     let mut slots_need_update = false;
-
-    let mut process_building = |cost: u32, is_active: &mut bool, id: &str, notif: &str| {
-        if remaining_credits >= cost as f64 {
+    let mut all_paid = true;
+    let priority = game_state.upkeep_priority.clone();
+    let category_count = priority.len();
+
+    // Returns false if the building's upkeep went unpaid.
+    let mut process_building = |cost: u32, is_active: &mut bool, is_mothballed: bool, kind: &str, id: &str, category: UpkeepCategory, rank: usize| -> bool {
+        if is_mothballed {
+            *is_active = false;
+            return true;
+        }
+        if remaining_credits - cost as f64 >= -CREDIT_LIMIT {
             remaining_credits -= cost as f64;
+            game_state.ledger.record(TransactionCategory::Upkeep, Some(id), -(cost as f64));
             if !*is_active {
                 *is_active = true;
             }
@@ -1571,43 +4254,86 @@ fn deduct_upkeep_system(game_state: &mut GameState) {
         } else {
             if *is_active {
                 *is_active = false;
-                add_notification(&mut game_state.notifications, format!("{} deactivated: unpaid upkeep", id), 0.0);
+                add_notification(
+                    &mut game_state.notifications,
+                    format!(
+                        "{} {} deactivated: unpaid upkeep. {} are upkeep priority {} of {}; {} Cr due, {:.0} Cr of credit left.",
+                        kind,
+                        id,
+                        category.label(),
+                        rank + 1,
+                        category_count,
+                        cost,
+                        (remaining_credits + CREDIT_LIMIT).max(0.0)
+                    ),
+                    0.0,
+                );
             }
+            all_paid = false;
             false
         }
     };
 
-    for fab in game_state.fabricators.iter_mut() {
-        if let Some(tier) = fab.available_tiers.get(fab.tier_index) {
-            process_building(tier.upkeep_cost, &mut fab.is_active, &format!("Fabricator {}", fab.id), "Fabricator");
-        }
-    }
-
-    for plant in game_state.processing_plants.iter_mut() {
-        if let Some(tier) = plant.available_tiers.get(plant.tier_index) {
-            process_building(tier.upkeep_cost, &mut plant.is_active, &format!("Processing Plant {}", plant.id), "Processing Plant");
-        }
-    }
-
-    for zone in game_state.zones.iter_mut() {
-        if let Some(tier) = zone.available_tiers.get(zone.current_tier_index) {
-            let was_active = zone.is_active;
-            process_building(tier.upkeep_cost, &mut zone.is_active, &format!("Zone {}", zone.id), "Zone");
-            if was_active != zone.is_active {
-                civic_index_needs_update = true;
-                slots_need_update = true;
+    for (rank, category) in priority.iter().copied().enumerate() {
+        match category {
+            UpkeepCategory::Fabricators => {
+                for fab in game_state.fabricators.iter_mut() {
+                    if let Some(tier) = fab.available_tiers.get(fab.tier_index) {
+                        if !process_building(game_state.modifiers.upkeep(category, tier.upkeep_cost), &mut fab.is_active, fab.is_mothballed, "Fabricator", &fab.id, category, rank) {
+                            fab.status = BuildingStatus::UnpaidUpkeep;
+                        }
+                    }
+                }
             }
-        }
-    }
-
-    for building in game_state.service_buildings.iter_mut() {
-        if let Some(tier) = building.available_tiers.get(building.current_tier_index) {
-            let was_active = building.is_active;
-            process_building(tier.upkeep_cost, &mut building.is_active, &format!("Service Building {}", building.id), "Service Building");
-            if was_active != building.is_active {
-                civic_index_needs_update = true;
-                if building.service_type == ServiceType::Education {
-                    slots_need_update = true;
+            UpkeepCategory::ProcessingPlants => {
+                for plant in game_state.processing_plants.iter_mut() {
+                    if let Some(tier) = plant.available_tiers.get(plant.tier_index) {
+                        if !process_building(game_state.modifiers.upkeep(category, tier.upkeep_cost), &mut plant.is_active, plant.is_mothballed, "Processing Plant", &plant.id, category, rank) {
+                            plant.status = BuildingStatus::UnpaidUpkeep;
+                        }
+                    }
+                }
+            }
+            UpkeepCategory::Zones => {
+                for zone in game_state.zones.iter_mut() {
+                    if let Some(tier) = zone.available_tiers.get(zone.current_tier_index) {
+                        let was_active = zone.is_active;
+                        let paid = process_building(game_state.modifiers.upkeep(category, tier.upkeep_cost), &mut zone.is_active, zone.is_mothballed, "Zone", &zone.id, category, rank);
+                        zone.status = if zone.is_mothballed {
+                            BuildingStatus::Mothballed
+                        } else if !paid {
+                            BuildingStatus::UnpaidUpkeep
+                        } else {
+                            BuildingStatus::Operational
+                        };
+                        if was_active != zone.is_active {
+                            civic_index_needs_update = true;
+                            slots_need_update = true;
+                        }
+                    }
+                }
+            }
+            UpkeepCategory::ServiceBuildings => {
+                for building in game_state.service_buildings.iter_mut() {
+                    if let Some(tier) = building.available_tiers.get(building.current_tier_index) {
+                        let was_active = building.is_active;
+                        let paid = process_building(game_state.modifiers.upkeep(category, tier.upkeep_cost), &mut building.is_active, building.is_mothballed, "Service Building", &building.id, category, rank);
+                        building.status = if building.is_mothballed {
+                            BuildingStatus::Mothballed
+                        } else if !paid {
+                            BuildingStatus::UnpaidUpkeep
+                        } else if staffing_fraction(building.assigned_specialists, tier.specialist_requirement) == 0.0 {
+                            BuildingStatus::Understaffed
+                        } else {
+                            BuildingStatus::Operational
+                        };
+                        if was_active != building.is_active {
+                            civic_index_needs_update = true;
+                            if building.service_type == ServiceType::Education {
+                                slots_need_update = true;
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -1621,11 +4347,16 @@ fn deduct_upkeep_system(game_state: &mut GameState) {
     if slots_need_update {
         update_total_specialist_slots(game_state);
     }
+
+    all_paid
 }
 
 fn upkeep_income_tick_system(mut game_state: ResMut<GameState>) {
     generate_income_system(&mut game_state);
-    deduct_upkeep_system(&mut game_state);
+    let upkeep_paid = deduct_upkeep_system(&mut game_state);
+    let debt_paid = service_debt_system(&mut game_state);
+    update_financial_status(&mut game_state, upkeep_paid && debt_paid);
+    game_state.ledger.advance_tick();
 }
 
 fn processing_plant_operations_tick_system(mut game_state: ResMut<GameState>, time: Res<Time>) {
@@ -1638,46 +4369,44 @@ fn fabricator_production_tick_system(mut game_state: ResMut<GameState>, time: Re
 
 
 
-// CORRECTED: This function is refactored to avoid borrow checker errors.
-fn workforce_assignment_system(mut game_state: ResMut<GameState>) {
-    // 1. Calculate total demand without holding mutable borrows
-    let extractor_demand = game_state.extractors.len() as u32 * 5;
-    let biodome_demand = game_state.bio_domes.len() as u32 * 10;
-    let research_demand = game_state.research_institutes.len() as u32 * 15;
-    let total_demand = extractor_demand + biodome_demand + research_demand;
-
-    // 2. Determine available workforce and update the total in GameState
-    let mut available_workforce = game_state.total_inhabitants.min(total_demand);
-    game_state.assigned_workforce = available_workforce;
-
-    // 3. Greedily assign workforce and update buildings one by one
-    // This avoids borrowing multiple fields of game_state mutably at the same time.
-    for extractor in &mut game_state.extractors {
-        if available_workforce >= 5 {
-            extractor.is_staffed = true;
-            available_workforce -= 5;
-        } else {
-            extractor.is_staffed = false;
-        }
+/// Hands out up to `full_crew` workers, or none if fewer than the minimum crew are left.
+fn assign_crew(available_workforce: &mut u32, full_crew: u32) -> u32 {
+    let crew = (*available_workforce).min(full_crew);
+    if crew == 0 || crew < minimum_crew(full_crew) {
+        return 0;
     }
+    *available_workforce -= crew;
+    crew
+}
 
-    for dome in &mut game_state.bio_domes {
-        if available_workforce >= 10 {
-            dome.is_staffed = true;
-            available_workforce -= 10;
-        } else {
-            dome.is_staffed = false;
+// CORRECTED: This function is refactored to avoid borrow checker errors.
+fn workforce_assignment_system(mut game_state: ResMut<GameState>) {
+    // Specialists hold their own positions and aren't part of the general labor pool.
+    let labor_pool = game_state.demographics.working_age.saturating_sub(game_state.assigned_specialists_total);
+    let mut available_workforce = labor_pool;
+    let category_order = effective_workforce_priority(&game_state);
+
+    // Buildings marked High are staffed first across all categories, then Normal, then Low.
+    // Within a band, categories follow the priority list.
+    for band in [WorkPriority::High, WorkPriority::Normal, WorkPriority::Low] {
+        for category in &category_order {
+            let crew = category.full_crew();
+            let slots: Vec<(&mut u32, &mut bool, WorkPriority)> = match category {
+                WorkforceCategory::Extractors => game_state.extractors.iter_mut().map(|b| (&mut b.assigned_workers, &mut b.is_staffed, b.work_priority)).collect(),
+                WorkforceCategory::BioDomes => game_state.bio_domes.iter_mut().map(|b| (&mut b.assigned_workers, &mut b.is_staffed, b.work_priority)).collect(),
+                WorkforceCategory::ResearchInstitutes => game_state.research_institutes.iter_mut().map(|b| (&mut b.assigned_workers, &mut b.is_staffed, b.work_priority)).collect(),
+            };
+            for (assigned_workers, is_staffed, priority) in slots {
+                if priority != band {
+                    continue;
+                }
+                *assigned_workers = assign_crew(&mut available_workforce, crew);
+                *is_staffed = *assigned_workers > 0;
+            }
         }
     }
 
-    for institute in &mut game_state.research_institutes {
-        if available_workforce >= 15 {
-            institute.is_staffed = true;
-            available_workforce -= 15;
-        } else {
-            institute.is_staffed = false;
-        }
-    }
+    game_state.assigned_workforce = labor_pool - available_workforce;
 }
 
 
@@ -1720,78 +4449,91 @@ fn game_tick_system(mut game_state: ResMut<GameState>) {
 
     // Determine if there's a power deficit that needs to be covered by stored power
     let power_deficit = if net_power < 0.0 { -net_power } else { 0.0 };
-    let stored_power = game_state.current_resources.entry(ResourceType::Power).or_insert(0.0);
+    let game_state = &mut *game_state;
+    let accounts = &mut game_state.resource_accounts;
 
     let has_sufficient_power = if net_power >= 0.0 {
-        *stored_power += net_power; // Add surplus to storage
+        // Add surplus to storage
+        accounts.produce(&mut game_state.current_resources, ResourceType::Power, net_power, f32::MAX, "Power Relays");
         true
     } else {
-        if *stored_power >= power_deficit {
-            *stored_power -= power_deficit; // Cover deficit from storage
-            true
-        } else {
-            *stored_power = 0.0; // Drain all stored power
-            false // Power outage!
-        }
+        // Cover deficit from storage, draining it entirely on an outage
+        let drawn = accounts.consume(&mut game_state.current_resources, ResourceType::Power, power_deficit, "Power Grid");
+        drawn >= power_deficit
     };
 
+    let total_capacity = BASE_STORAGE_CAPACITY
+        + game_state.storage_silos.len() as f32 * STORAGE_SILO_CAPACITY;
+
+    // --- Building Status ---
+    let paste_full = game_state.current_resources.get(ResourceType::NutrientPaste) >= total_capacity;
+    let ore_full = game_state.current_resources.get(ResourceType::FerrocreteOre) >= total_capacity;
+    for extractor in game_state.extractors.iter_mut() {
+        extractor.status = staffed_building_status(extractor.is_staffed, has_sufficient_power, ore_full);
+    }
+    for dome in game_state.bio_domes.iter_mut() {
+        dome.status = staffed_building_status(dome.is_staffed, has_sufficient_power, paste_full);
+    }
+    for institute in game_state.research_institutes.iter_mut() {
+        institute.status = staffed_building_status(institute.is_staffed, has_sufficient_power, false);
+    }
+
     // --- Resource Production (only if powered) ---
     if has_sufficient_power {
         // First, count the number of staffed buildings to release immutable borrows.
-        let staffed_bio_domes = game_state.bio_domes.iter().filter(|d| d.is_staffed).count() as f32;
-        let staffed_extractors = game_state.extractors.iter().filter(|e| e.is_staffed).count() as f32;
-
-        let total_capacity = BASE_STORAGE_CAPACITY
-            + game_state.storage_silos.len() as f32 * STORAGE_SILO_CAPACITY;
+        let staffed_bio_domes: f32 = game_state.bio_domes.iter().map(|d| staffing_fraction(d.assigned_workers, BIO_DOME_CREW)).sum();
+        let staffed_extractors: f32 = game_state.extractors.iter().map(|e| staffing_fraction(e.assigned_workers, EXTRACTOR_CREW)).sum();
 
         // Now, update resources without holding the previous borrows.
-        let nutrient_paste_amount = game_state.current_resources.entry(ResourceType::NutrientPaste).or_insert(0.0);
-        *nutrient_paste_amount = (*nutrient_paste_amount + 5.0 * staffed_bio_domes).min(total_capacity);
-
-        let ferrocrete_ore_amount = game_state.current_resources.entry(ResourceType::FerrocreteOre).or_insert(0.0);
-        *ferrocrete_ore_amount = (*ferrocrete_ore_amount + 2.5 * staffed_extractors).min(total_capacity);
+        let accounts = &mut game_state.resource_accounts;
+        let stock = &mut game_state.current_resources;
+        let paste_output = game_state.modifiers.apply(STAT_BIO_DOME_OUTPUT, 5.0 * staffed_bio_domes);
+        accounts.produce(stock, ResourceType::NutrientPaste, paste_output, total_capacity, "Bio-Domes");
+        let ore_output = game_state.modifiers.apply(STAT_EXTRACTOR_OUTPUT, 2.5 * staffed_extractors);
+        accounts.produce(stock, ResourceType::FerrocreteOre, ore_output, total_capacity, "Extractors");
     }
 
     // Update food status for happiness calculation
     game_state.simulated_has_sufficient_nutrient_paste = game_state
         .current_resources
-        .get(&ResourceType::NutrientPaste)
-        .unwrap_or(&0.0)
-        > &0.0;
+        .get(ResourceType::NutrientPaste)
+        > 0.0;
 }
 
 fn food_consumption_system(mut game_state: ResMut<GameState>) {
     let consumption = game_state.total_inhabitants as f32 * FOOD_CONSUMPTION_PER_PERSON;
-    let entry = game_state
-        .current_resources
-        .entry(ResourceType::NutrientPaste)
-        .or_insert(0.0);
-
-    if *entry >= consumption {
-        *entry -= consumption;
-    } else {
-        *entry = 0.0;
-    }
-
-    game_state.simulated_has_sufficient_nutrient_paste = *entry > 0.0;
+    let game_state = &mut *game_state;
+    game_state.resource_accounts.consume(
+        &mut game_state.current_resources,
+        ResourceType::NutrientPaste,
+        consumption,
+        "Inhabitants",
+    );
+
+    game_state.simulated_has_sufficient_nutrient_paste =
+        game_state.current_resources.get(ResourceType::NutrientPaste) > 0.0;
 }
 
-fn clamp_resource_system(mut game_state: ResMut<GameState>) {
-    for value in game_state.current_resources.values_mut() {
-        if *value < 0.0 {
-            *value = 0.0;
-        }
-    }
+fn close_resource_accounts_system(mut game_state: ResMut<GameState>) {
+    let capacity = BASE_STORAGE_CAPACITY + game_state.storage_silos.len() as f32 * STORAGE_SILO_CAPACITY;
+    let game_state = &mut *game_state;
+    game_state.resource_accounts.enforce_capacity(&mut game_state.current_resources, capacity);
+    game_state.resource_accounts.end_tick();
 }
 
 
 fn update_colony_stats_system(mut stats: ResMut<ColonyStats>, game_state: Res<GameState>) {
     stats.total_housing = game_state.available_housing_capacity;
-    stats.total_jobs = game_state.assigned_workforce;
+    stats.total_jobs = game_state.labor_market.total_jobs();
+    stats.unemployed = game_state.labor_market.unemployed;
+    stats.homeless = game_state.labor_market.homeless;
+    stats.children = game_state.demographics.children;
+    stats.working_age = game_state.demographics.working_age;
+    stats.elderly = game_state.demographics.elderly;
     stats.happiness = game_state.colony_happiness;
     stats.credits = game_state.credits;
     stats.net_power = game_state.total_generated_power - game_state.total_consumed_power;
-    stats.nutrient_paste = *game_state.current_resources.get(&ResourceType::NutrientPaste).unwrap_or(&0.0);
+    stats.nutrient_paste = game_state.current_resources.get(ResourceType::NutrientPaste);
 }
 
 
@@ -1844,7 +4586,33 @@ fn load_game_system(
                 if file.read_to_string(&mut json_str).is_ok() {
                     match serde_json::from_str::<GameState>(&json_str) {
                         Ok(mut loaded_state) => {
+                            // Saves from before a specialist category existed get it at the lowest priority.
+                            for category in default_specialist_priority() {
+                                if !loaded_state.specialist_priority.contains(&category) {
+                                    loaded_state.specialist_priority.push(category);
+                                }
+                            }
+                            // An in-progress project from before research fields goes back to the front
+                            // of the queue, its points spread over the fields it now needs.
+                            if let Some((tech, progress)) = loaded_state.legacy_research_progress.take() {
+                                let costs = tech_node(tech).research_costs;
+                                let total: u32 = costs.iter().map(|(_, c)| *c).sum();
+                                let share = (progress / total.max(1) as f32).min(1.0);
+                                loaded_state.research_progress.insert(
+                                    tech,
+                                    costs.iter().map(|(field, cost)| (*field, *cost as f32 * share)).collect(),
+                                );
+                                if !loaded_state.research_queue.contains(&tech) {
+                                    loaded_state.research_queue.insert(0, tech);
+                                }
+                            }
+                            // Older saves kept the ledger history newest first.
+                            let history = &mut loaded_state.ledger.history;
+                            if history.front().zip(history.back()).is_some_and(|(first, last)| first.tick > last.tick) {
+                                history.make_contiguous().reverse();
+                            }
                             add_notification(&mut loaded_state.notifications, "Game Loaded.".to_string(), 0.0);
+                            commands.insert_resource(SpatialIndex::from_state(&loaded_state));
                             commands.insert_resource(loaded_state);
                             println!("Game loaded successfully from {}", SAVE_PATH);
                         }
@@ -1858,14 +4626,18 @@ fn load_game_system(
 }
 
 // --- New Data-Driven Construction ---
-pub fn add_extractor(game_state: &mut GameState) {
-    game_state.extractors.push(ExtractorData { id: generate_unique_id(), is_staffed: false });
+pub fn add_extractor(game_state: &mut GameState, position: Option<(f32, f32)>) -> String {
+    let id = generate_unique_id();
+    game_state.extractors.push(ExtractorData { id: id.clone(), is_staffed: false, assigned_workers: 0, work_priority: WorkPriority::Normal, status: BuildingStatus::UnderConstruction, position });
+    id
 }
 pub fn add_bio_dome(game_state: &mut GameState) {
-    game_state.bio_domes.push(BioDomeData { id: generate_unique_id(), is_staffed: false });
+    game_state.bio_domes.push(BioDomeData { id: generate_unique_id(), is_staffed: false, assigned_workers: 0, work_priority: WorkPriority::Normal, status: BuildingStatus::UnderConstruction });
 }
-pub fn add_power_relay(game_state: &mut GameState) {
-    game_state.power_relays.push(PowerRelayData { id: generate_unique_id() });
+pub fn add_power_relay(game_state: &mut GameState, position: Option<(f32, f32)>) -> String {
+    let id = generate_unique_id();
+    game_state.power_relays.push(PowerRelayData { id: id.clone(), position });
+    id
 }
 pub fn add_research_institute(game_state: &mut GameState) {
     if !game_state.unlocked_techs.contains(&Tech::BasicConstructionProtocols) {
@@ -1876,7 +4648,7 @@ pub fn add_research_institute(game_state: &mut GameState) {
         );
         return;
     }
-    game_state.research_institutes.push(ResearchInstituteData { id: generate_unique_id(), is_staffed: false });
+    game_state.research_institutes.push(ResearchInstituteData { id: generate_unique_id(), is_staffed: false, assigned_workers: 0, work_priority: WorkPriority::Normal, status: BuildingStatus::UnderConstruction, assigned_specialists: 0, manual_lock: false, field: ResearchField::default() });
 }
 pub fn add_storage_silo(game_state: &mut GameState) {
     if !game_state.unlocked_techs.contains(&Tech::BasicConstructionProtocols) {
@@ -1888,4 +4660,15 @@ pub fn add_storage_silo(game_state: &mut GameState) {
         return;
     }
     game_state.storage_silos.push(StorageSiloData { id: generate_unique_id() });
+}
+pub fn add_spaceport(game_state: &mut GameState) {
+    if !game_state.unlocked_techs.contains(&Tech::BasicConstructionProtocols) {
+        add_notification(
+            &mut game_state.notifications,
+            "Requires BasicConstructionProtocols to build Spaceport.".to_string(),
+            0.0,
+        );
+        return;
+    }
+    game_state.spaceports.push(SpaceportData { id: generate_unique_id(), next_shuttle_in: SHUTTLE_INTERVAL_TICKS });
 }
\ No newline at end of file
@@ -1,2 +1,3 @@
 pub mod population;
+pub mod spatial;
 pub mod tutorial;
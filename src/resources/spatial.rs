@@ -0,0 +1,152 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::game_state::{GameState, ServiceType};
+
+/// Distance between neighbouring building sites on the colony grid.
+pub const SITE_SPACING: f32 = 30.0;
+/// Side length of one spatial index cell; about two service radii so most queries touch few cells.
+pub const SPATIAL_CELL_SIZE: f32 = 100.0;
+/// How far a power relay's supply reaches.
+pub const POWER_RELAY_RANGE: f32 = 90.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpatialKind {
+    Habitation,
+    Service(ServiceType),
+    PowerRelay,
+    /// Extractors, fabricators and processing plants: workplaces that pollute
+    Industry,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpatialEntry {
+    pub id: String,
+    pub kind: SpatialKind,
+    pub position: (f32, f32),
+}
+
+/// Uniform grid over every placed building. Construction and demolition update it as they happen,
+/// and loading a save rebuilds it with `from_state`.
+/// Coverage, power reach, placement and map overlays query it instead of scanning building lists.
+#[derive(Resource)]
+pub struct SpatialIndex {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<SpatialEntry>>,
+    locations: HashMap<String, (f32, f32)>,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        SpatialIndex { cell_size: SPATIAL_CELL_SIZE, cells: HashMap::new(), locations: HashMap::new() }
+    }
+}
+
+impl SpatialIndex {
+    /// Indexes every placed building in `game_state`, e.g. after loading a save.
+    pub fn from_state(game_state: &GameState) -> Self {
+        let mut index = SpatialIndex::default();
+        for structure in &game_state.habitation_structures {
+            if let Some(position) = structure.position {
+                index.upsert(&structure.id, SpatialKind::Habitation, position);
+            }
+        }
+        for building in &game_state.service_buildings {
+            if let Some(position) = building.position {
+                index.upsert(&building.id, SpatialKind::Service(building.service_type), position);
+            }
+        }
+        for relay in &game_state.power_relays {
+            if let Some(position) = relay.position {
+                index.upsert(&relay.id, SpatialKind::PowerRelay, position);
+            }
+        }
+        let industry = game_state
+            .extractors
+            .iter()
+            .map(|e| (&e.id, e.position))
+            .chain(game_state.fabricators.iter().map(|f| (&f.id, f.position)))
+            .chain(game_state.processing_plants.iter().map(|p| (&p.id, p.position)));
+        for (id, position) in industry {
+            if let Some(position) = position {
+                index.upsert(id, SpatialKind::Industry, position);
+            }
+        }
+        index
+    }
+
+    fn cell_of(&self, position: (f32, f32)) -> (i32, i32) {
+        ((position.0 / self.cell_size).floor() as i32, (position.1 / self.cell_size).floor() as i32)
+    }
+
+    /// Adds a building, or moves it if it is already indexed somewhere else.
+    pub fn upsert(&mut self, id: &str, kind: SpatialKind, position: (f32, f32)) {
+        if self.locations.get(id) == Some(&position) {
+            return;
+        }
+        self.remove(id);
+        let cell = self.cell_of(position);
+        self.cells.entry(cell).or_default().push(SpatialEntry { id: id.to_string(), kind, position });
+        self.locations.insert(id.to_string(), position);
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        let Some(position) = self.locations.remove(id) else {
+            return;
+        };
+        let cell = self.cell_of(position);
+        if let Some(entries) = self.cells.get_mut(&cell) {
+            entries.retain(|e| e.id != id);
+            if entries.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.locations.contains_key(id)
+    }
+
+    /// Every indexed building within `radius` of `center`.
+    pub fn query_radius(&self, center: (f32, f32), radius: f32) -> impl Iterator<Item = &SpatialEntry> {
+        let (min_x, min_y) = self.cell_of((center.0 - radius, center.1 - radius));
+        let (max_x, max_y) = self.cell_of((center.0 + radius, center.1 + radius));
+        (min_x..=max_x)
+            .flat_map(move |x| (min_y..=max_y).map(move |y| (x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .filter(move |e| {
+                let dx = e.position.0 - center.0;
+                let dy = e.position.1 - center.1;
+                dx * dx + dy * dy <= radius * radius
+            })
+    }
+
+    /// True when no building sits within half a site of `site`.
+    pub fn is_site_free(&self, site: (f32, f32)) -> bool {
+        self.query_radius(site, SITE_SPACING / 2.0).next().is_none()
+    }
+
+    /// True when `site` is within reach of a power relay.
+    pub fn is_powered(&self, site: (f32, f32)) -> bool {
+        self.query_radius(site, POWER_RELAY_RANGE).any(|e| e.kind == SpatialKind::PowerRelay)
+    }
+
+    /// The free grid site closest to the colony centre, searched in rings outward.
+    pub fn next_free_site(&self) -> (f32, f32) {
+        for ring in 0_i32.. {
+            for x in -ring..=ring {
+                for y in -ring..=ring {
+                    if x.abs().max(y.abs()) != ring {
+                        continue;
+                    }
+                    let site = (x as f32 * SITE_SPACING, y as f32 * SITE_SPACING);
+                    if self.is_site_free(site) {
+                        return site;
+                    }
+                }
+            }
+        }
+        unreachable!("the site grid is unbounded")
+    }
+}
@@ -116,7 +116,7 @@ pub fn get_tutorial_steps() -> Vec<TooltipStep> {
 }
 
 // Placeholder condition helpers
-use crate::game_state::{GameState, ResourceType, ServiceType};
+use crate::game_state::{BuildingStatus, GameState, ResourceType, ServiceType};
 use crate::ui::{AppType, CurrentApp, SelectedTech, UiTag};
 
 fn has_entity_with_tag(world: &World, tag: &str) -> bool {
@@ -127,13 +127,31 @@ fn has_entity_with_tag(world: &World, tag: &str) -> bool {
 
 fn entity_has_flag(world: &World, entity: &str, flag: &str) -> bool {
     let gs = world.resource::<GameState>();
-    match (entity, flag) {
-        ("extractor", "needs_power") => {
-            !gs.extractors.is_empty()
-                && (gs.total_generated_power < gs.total_consumed_power)
-        }
-        _ => false,
-    }
+    let wanted = match flag {
+        "operational" => BuildingStatus::Operational,
+        "needs_power" => BuildingStatus::Unpowered,
+        "needs_workers" => BuildingStatus::Understaffed,
+        "missing_inputs" => BuildingStatus::MissingInputs,
+        "storage_full" => BuildingStatus::OutputStorageFull,
+        "unpaid_upkeep" => BuildingStatus::UnpaidUpkeep,
+        "mothballed" => BuildingStatus::Mothballed,
+        _ => return false,
+    };
+    let statuses: Vec<BuildingStatus> = match entity {
+        "extractor" => gs.extractors.iter().map(|b| b.status).collect(),
+        "bio_dome" => gs.bio_domes.iter().map(|b| b.status).collect(),
+        "research_institute" => gs.research_institutes.iter().map(|b| b.status).collect(),
+        "fabricator" => gs.fabricators.iter().map(|b| b.status).collect(),
+        "processing_plant" => gs.processing_plants.iter().map(|b| b.status).collect(),
+        "zone" => gs.zones.iter().map(|b| b.status).collect(),
+        "service_building" => gs.service_buildings.iter().map(|b| b.status).collect(),
+        _ => return false,
+    };
+    // Staffing is reported ahead of power, so a colony-wide deficit also counts as needing power.
+    let power_deficit = wanted == BuildingStatus::Unpowered
+        && !statuses.is_empty()
+        && gs.total_generated_power < gs.total_consumed_power;
+    power_deficit || statuses.contains(&wanted)
 }
 
 fn entity_produces_resource(world: &World, entity: &str) -> bool {
@@ -141,17 +159,16 @@ fn entity_produces_resource(world: &World, entity: &str) -> bool {
     match entity {
         "extractor" => gs
             .current_resources
-            .get(&ResourceType::FerrocreteOre)
-            .copied()
-            .unwrap_or(0.0)
+            .get(ResourceType::FerrocreteOre)
             > 200.0,
         _ => false,
     }
 }
 
+/// The labor market is empty until its first tick, so no jobs yet doesn't count as a shortage.
 fn out_of_workers(world: &World) -> bool {
     let gs = world.resource::<GameState>();
-    gs.total_inhabitants <= gs.assigned_workforce
+    gs.labor_market.total_jobs() > 0 && gs.labor_market.unemployed == 0
 }
 
 fn population_increased(world: &World) -> bool {
@@ -0,0 +1,219 @@
+use bevy::prelude::*;
+
+use crate::components::citizen::{Citizen, Home, Inhabitant, Needs, PersonalHappiness, Specialist, Workplace};
+use crate::game_state::{AgeCohort, GameState, ServiceCoverage};
+use crate::systems::happiness::{HappinessBreakdown, HappinessResource};
+
+const HOMELESS_HAPPINESS_PENALTY: f32 = 10.0;
+const UNEMPLOYED_HAPPINESS_PENALTY: f32 = 5.0;
+
+/// Keeps one citizen entity per inhabitant while agent mode is on, and despawns them all when it is off.
+pub fn sync_citizen_agents_system(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    citizens: Query<(Entity, &Citizen, Has<Specialist>, Option<&AgeCohort>)>,
+    mut next_serial: Local<u32>,
+) {
+    let mut agents: Vec<(Entity, Citizen, bool, Option<AgeCohort>)> =
+        citizens.iter().map(|(e, c, s, a)| (e, *c, s, a.copied())).collect();
+    if !game_state.agent_mode {
+        for (entity, ..) in agents {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    agents.sort_by_key(|(_, citizen, ..)| *citizen);
+    let target = game_state.total_inhabitants as usize;
+    // The most recent arrivals are the first to go when the population shrinks.
+    while agents.len() > target {
+        if let Some((entity, ..)) = agents.pop() {
+            commands.entity(entity).despawn();
+        }
+    }
+    for _ in agents.len()..target {
+        *next_serial += 1;
+        commands.spawn((
+            Inhabitant,
+            Citizen(*next_serial),
+            AgeCohort::Children,
+            Home::default(),
+            Workplace::default(),
+            Needs::default(),
+            PersonalHappiness::default(),
+        ));
+    }
+
+    // The longest-standing colonists are the elderly and the newest are the children,
+    // sized to match the aggregate cohorts.
+    let elderly = game_state.demographics.elderly as usize;
+    let children_start = agents.len().saturating_sub(game_state.demographics.children as usize);
+    for (index, (entity, _, is_specialist, current)) in agents.iter_mut().enumerate() {
+        let cohort = if index < elderly {
+            AgeCohort::Elderly
+        } else if index >= children_start {
+            AgeCohort::Children
+        } else {
+            AgeCohort::WorkingAge
+        };
+        if *current != Some(cohort) {
+            commands.entity(*entity).insert(cohort);
+            *current = Some(cohort);
+        }
+        if cohort != AgeCohort::WorkingAge && *is_specialist {
+            commands.entity(*entity).remove::<Specialist>();
+            *is_specialist = false;
+        }
+    }
+
+    // Promote the longest-standing working-age colonists first, demote the newest first.
+    let working_age: Vec<_> = agents.iter().filter(|(.., cohort)| *cohort == Some(AgeCohort::WorkingAge)).collect();
+    let target_specialists = (game_state.assigned_specialists_total as usize).min(working_age.len());
+    let specialists = working_age.iter().filter(|(_, _, is_specialist, _)| *is_specialist).count();
+    if specialists < target_specialists {
+        for (entity, ..) in working_age.iter().filter(|(_, _, s, _)| !*s).take(target_specialists - specialists) {
+            commands.entity(*entity).insert(Specialist);
+        }
+    } else if specialists > target_specialists {
+        for (entity, ..) in working_age.iter().rev().filter(|(_, _, s, _)| *s).take(specialists - target_specialists) {
+            commands.entity(*entity).remove::<Specialist>();
+        }
+    }
+}
+
+/// Claims a place for an existing placement, or clears it if the place is gone or full.
+fn keep_slot(slot: &mut Option<String>, openings: &mut [(String, u32)]) {
+    if let Some(id) = slot.as_ref() {
+        match openings.iter_mut().find(|(opening, _)| opening == id) {
+            Some((_, free)) if *free > 0 => *free -= 1,
+            _ => *slot = None,
+        }
+    }
+}
+
+fn take_slot(openings: &mut [(String, u32)]) -> Option<String> {
+    openings.iter_mut().find(|(_, free)| *free > 0).map(|(id, free)| {
+        *free -= 1;
+        id.clone()
+    })
+}
+
+/// Gives each citizen a home and a workplace, keeping existing placements where they still fit.
+pub fn assign_citizen_agents_system(
+    game_state: Res<GameState>,
+    mut citizens: Query<(&Citizen, &mut Home, &mut Workplace, Has<Specialist>, &AgeCohort)>,
+) {
+    if !game_state.agent_mode {
+        return;
+    }
+
+    let mut homes: Vec<(String, u32)> = game_state
+        .habitation_structures
+        .iter()
+        .map(|h| (h.id.clone(), h.current_inhabitants))
+        .collect();
+
+    let mut worker_posts: Vec<(String, u32)> = Vec::new();
+    worker_posts.extend(game_state.extractors.iter().map(|b| (b.id.clone(), b.assigned_workers)));
+    worker_posts.extend(game_state.bio_domes.iter().map(|b| (b.id.clone(), b.assigned_workers)));
+    worker_posts.extend(game_state.research_institutes.iter().map(|b| (b.id.clone(), b.assigned_workers)));
+
+    let mut specialist_posts: Vec<(String, u32)> = Vec::new();
+    specialist_posts.extend(game_state.service_buildings.iter().map(|b| (b.id.clone(), b.assigned_specialists)));
+    specialist_posts.extend(game_state.fabricators.iter().map(|b| (b.id.clone(), b.assigned_specialists)));
+    specialist_posts.extend(game_state.processing_plants.iter().map(|b| (b.id.clone(), b.assigned_specialists)));
+    specialist_posts.extend(game_state.zones.iter().map(|b| (b.id.clone(), b.assigned_specialists)));
+    specialist_posts.extend(game_state.habitation_structures.iter().map(|b| (b.id.clone(), b.assigned_specialists)));
+    specialist_posts.extend(game_state.research_institutes.iter().map(|b| (b.id.clone(), b.assigned_specialists)));
+
+    let mut agents: Vec<_> = citizens.iter_mut().collect();
+    agents.sort_by_key(|(citizen, ..)| **citizen);
+
+    for (_, home, workplace, is_specialist, cohort) in agents.iter_mut() {
+        keep_slot(&mut home.0, &mut homes);
+        if **cohort != AgeCohort::WorkingAge {
+            workplace.0 = None;
+            continue;
+        }
+        let posts = if *is_specialist { &mut specialist_posts } else { &mut worker_posts };
+        keep_slot(&mut workplace.0, posts);
+    }
+    for (_, home, workplace, is_specialist, cohort) in agents.iter_mut() {
+        if home.0.is_none() {
+            home.0 = take_slot(&mut homes);
+        }
+        if workplace.0.is_none() && **cohort == AgeCohort::WorkingAge {
+            let posts = if *is_specialist { &mut specialist_posts } else { &mut worker_posts };
+            workplace.0 = take_slot(posts);
+        }
+    }
+}
+
+/// Scores each citizen's needs and happiness, then derives the colony aggregates from them.
+pub fn citizen_wellbeing_system(
+    mut game_state: ResMut<GameState>,
+    mut breakdown: ResMut<HappinessBreakdown>,
+    mut happiness_score: ResMut<HappinessResource>,
+    coverage: Res<ServiceCoverage>,
+    mut citizens: Query<(&Home, &Workplace, &mut Needs, &mut PersonalHappiness, Has<Specialist>, &AgeCohort)>,
+) {
+    if !game_state.agent_mode || citizens.is_empty() {
+        return;
+    }
+
+    let food = if game_state.simulated_has_sufficient_nutrient_paste { 1.0 } else { 0.0 };
+    let services = if coverage.coverage.is_empty() {
+        1.0
+    } else {
+        coverage.coverage.values().map(|r| r.min(1.0)).sum::<f32>() / coverage.coverage.len() as f32
+    };
+    // The household's own score (colony-wide factors plus local ones) sets the baseline;
+    // shelter and work are personal, so the baseline leaves them out in agent mode.
+    let colony_happiness = game_state.colony_happiness;
+
+    let mut total_happiness = 0.0;
+    let mut count = 0;
+    let (mut employed_workers, mut assigned_specialists, mut unemployed, mut homeless) = (0, 0, 0, 0);
+    for (home, workplace, mut needs, mut happiness, is_specialist, cohort) in citizens.iter_mut() {
+        let can_work = *cohort == AgeCohort::WorkingAge;
+        needs.food = food;
+        needs.shelter = if home.0.is_some() { 1.0 } else { 0.0 };
+        needs.employment = if workplace.0.is_some() || !can_work { 1.0 } else { 0.0 };
+        needs.services = services;
+
+        let baseline = home
+            .0
+            .as_ref()
+            .and_then(|id| game_state.household_happiness.get(id))
+            .copied()
+            .unwrap_or(colony_happiness);
+        happiness.0 = (baseline
+            - (1.0 - needs.shelter) * HOMELESS_HAPPINESS_PENALTY
+            - (1.0 - needs.employment) * UNEMPLOYED_HAPPINESS_PENALTY)
+            .clamp(0.0, 100.0);
+        total_happiness += happiness.0;
+        count += 1;
+
+        match (can_work, workplace.0.is_some(), is_specialist) {
+            (false, _, _) => {}
+            (true, false, _) => unemployed += 1,
+            (true, true, true) => assigned_specialists += 1,
+            (true, true, false) => employed_workers += 1,
+        }
+        if home.0.is_none() {
+            homeless += 1;
+        }
+    }
+
+    game_state.labor_market.employed_workers = employed_workers;
+    game_state.labor_market.assigned_specialists = assigned_specialists;
+    game_state.labor_market.unemployed = unemployed;
+    game_state.labor_market.homeless = homeless;
+    game_state.colony_happiness = total_happiness / count as f32;
+    happiness_score.score = game_state.colony_happiness;
+    let personal = game_state.colony_happiness - breakdown.total;
+    if personal.abs() > f32::EPSILON {
+        breakdown.push("Homes and jobs", personal, "Citizens without a home or a job are less happy");
+        breakdown.total = game_state.colony_happiness;
+    }
+}
@@ -1,7 +1,12 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-use crate::game_state::{GameState, ServiceCoverage, ServiceType};
+use crate::game_state::{
+    bankruptcy_happiness_penalty, tax_happiness_penalty, GameState, HabitationStructure, ModifierOp, ModifierSource,
+    ServiceCoverage, ALL_SERVICE_TYPES, STAT_HAPPINESS, UNEMPLOYMENT_TOLERANCE,
+};
+use crate::resources::spatial::{SpatialIndex, SpatialKind};
 use crate::resources::population::PopulationResource; // not used but show typical cross refs
 
 #[derive(Resource, Serialize, Deserialize, Clone)]
@@ -15,60 +20,256 @@ impl Default for HappinessResource {
     }
 }
 
-pub fn calculate_colony_happiness(game_state: &mut GameState, coverage: &ServiceCoverage) {
-    let mut happiness_score = 50.0;
+/// Distance to the nearest workplace a commute can cover before it starts to grate.
+pub const COMMUTE_FREE_DISTANCE: f32 = 60.0;
+/// Happiness lost per unit of commute beyond the free distance.
+pub const COMMUTE_PENALTY_PER_UNIT: f32 = 0.05;
+pub const MAX_COMMUTE_PENALTY: f32 = 10.0;
+/// How far industrial pollution carries.
+pub const POLLUTION_RADIUS: f32 = 90.0;
+/// Pollution from each production building in range, felt in full right next to it.
+pub const POLLUTION_PER_INDUSTRY: f32 = 1.5;
+/// Pollution from each power relay in range.
+pub const POLLUTION_PER_RELAY: f32 = 2.0;
+pub const MAX_POLLUTION_PENALTY: f32 = 15.0;
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+/// One signed contribution to a happiness score, with the reason behind it.
+#[derive(Debug, Clone)]
+pub struct HappinessFactor {
+    pub label: String,
+    pub value: f32,
+    pub reason: String,
+}
+
+/// Every term that makes up the colony happiness score. The factor values add up to `total`.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct HappinessBreakdown {
+    pub factors: Vec<HappinessFactor>,
+    pub total: f32,
+}
+
+impl HappinessBreakdown {
+    pub fn push(&mut self, label: impl Into<String>, value: f32, reason: impl Into<String>) {
+        self.factors.push(HappinessFactor { label: label.into(), value, reason: reason.into() });
+    }
+}
+
+/// Happiness from coverage of one service: +5 when fully covered, up to -10 when missing.
+fn service_happiness(ratio: f32) -> f32 {
+    if ratio >= 1.0 { 5.0 } else { -(1.0 - ratio) * 10.0 }
+}
+
+/// Happiness shared by everyone in the colony: food, homelessness, jobs, the legacy structure,
+/// civic standing, taxes and bankruptcy. In agent mode shelter and jobs are scored per citizen instead.
+fn colony_baseline(game_state: &GameState) -> HappinessBreakdown {
+    let mut breakdown = HappinessBreakdown::default();
+    breakdown.push("Base", 50.0, "Every colony starts at neutral morale");
 
     if game_state.simulated_has_sufficient_nutrient_paste {
-        happiness_score += 10.0;
+        breakdown.push("Food", 10.0, "Nutrient paste supply is sufficient");
     } else {
-        happiness_score -= 25.0;
+        breakdown.push("Food", -25.0, "The colony is short of nutrient paste");
     }
 
-    if game_state.total_inhabitants > game_state.available_housing_capacity {
+    if !game_state.agent_mode && game_state.total_inhabitants > game_state.available_housing_capacity {
         let homeless = game_state.total_inhabitants - game_state.available_housing_capacity;
-        happiness_score -= (homeless as f32) * 2.0;
-    } else if game_state.available_housing_capacity > 0 && game_state.total_inhabitants > 0 {
-        let occupancy_ratio = game_state.total_inhabitants as f32 / game_state.available_housing_capacity as f32;
-        if occupancy_ratio <= 0.9 {
-            happiness_score += 5.0;
-        } else if occupancy_ratio < 1.0 {
-            happiness_score += 2.0;
-        }
+        breakdown.push("Homelessness", -(homeless as f32) * 2.0, format!("{} inhabitants have no home", homeless));
     }
 
-    if let Some(structure) = &game_state.legacy_structure {
-        if let Some(tier) = structure.available_tiers.get(structure.current_tier_index) {
-            happiness_score += tier.happiness_bonus;
-        }
+    let unemployment = game_state.labor_market.unemployment_rate();
+    if !game_state.agent_mode && unemployment > UNEMPLOYMENT_TOLERANCE {
+        breakdown.push(
+            "Unemployment",
+            -((unemployment - UNEMPLOYMENT_TOLERANCE) * 40.0).min(15.0),
+            format!("{:.0}% unemployed, above the {:.0}% tolerated", unemployment * 100.0, UNEMPLOYMENT_TOLERANCE * 100.0),
+        );
+    }
+
+    let civic = (game_state.civic_index as f32 / 10.0).min(5.0);
+    if civic > 0.0 {
+        breakdown.push("Civic index", civic, format!("Civic index {}", game_state.civic_index));
+    }
+    let taxes = tax_happiness_penalty(&game_state.tax_rates);
+    if taxes > 0.0 {
+        breakdown.push("Taxes", -taxes, "Tax rates above what inhabitants tolerate");
+    }
+    let bankruptcy = bankruptcy_happiness_penalty(game_state);
+    if bankruptcy > 0.0 {
+        breakdown.push("Bankruptcy", -bankruptcy, "The treasury is in default");
+    }
+
+    for modifier in game_state.modifiers.for_stat(STAT_HAPPINESS) {
+        let reason = match (&modifier.source, modifier.remaining_ticks) {
+            (ModifierSource::Legacy(_), _) => "The legacy structure inspires the colony".to_string(),
+            (_, Some(ticks)) => format!("Wears off in {} ticks", ticks),
+            (_, None) => "Standing effect".to_string(),
+        };
+        let value = match modifier.op {
+            ModifierOp::Add(value) => value,
+            ModifierOp::Multiply(factor) => breakdown.factors.iter().map(|f| f.value).sum::<f32>() * (factor - 1.0),
+        };
+        breakdown.push(modifier.source.label(), value, reason);
+    }
+    breakdown
+}
+
+/// Happiness from a structure's own occupancy; roomy homes are pleasant, full ones are not.
+fn crowding_happiness(structure: &HabitationStructure) -> f32 {
+    let capacity = structure.available_tiers.get(structure.tier_index).map_or(0, |t| t.housing_capacity);
+    if capacity == 0 || structure.current_inhabitants == 0 {
+        return 0.0;
+    }
+    let occupancy_ratio = structure.current_inhabitants as f32 / capacity as f32;
+    if occupancy_ratio <= 0.9 {
+        5.0
+    } else if occupancy_ratio < 1.0 {
+        2.0
+    } else {
+        0.0
     }
-    happiness_score += (game_state.civic_index as f32 / 10.0).min(5.0);
-
-    let service_types = [
-        ServiceType::Wellness,
-        ServiceType::Security,
-        ServiceType::Education,
-        ServiceType::Recreation,
-        ServiceType::Spiritual,
-    ];
-
-    for service_type in service_types {
-        if let Some(ratio) = coverage.coverage.get(&service_type) {
-            if *ratio >= 1.0 {
-                happiness_score += 5.0;
-            } else {
-                happiness_score -= (1.0 - ratio) * 10.0;
-            }
+}
+
+/// Commute is the distance to the nearest extractor, fabricator or processing plant; pollution comes
+/// from the industry and power relays in range. Returns the (commute, pollution) penalties.
+fn location_penalties(game_state: &GameState, spatial_index: &SpatialIndex, position: (f32, f32)) -> (f32, f32) {
+    // Beyond this reach the commute penalty is already at its cap.
+    let commute_reach = COMMUTE_FREE_DISTANCE + MAX_COMMUTE_PENALTY / COMMUTE_PENALTY_PER_UNIT;
+    let nearest_workplace = spatial_index
+        .query_radius(position, commute_reach)
+        .filter(|entry| entry.kind == SpatialKind::Industry)
+        .map(|entry| distance(position, entry.position))
+        .min_by(f32::total_cmp);
+    let has_workplaces = game_state.extractors.iter().any(|e| e.position.is_some())
+        || game_state.fabricators.iter().any(|f| f.position.is_some())
+        || game_state.processing_plants.iter().any(|p| p.position.is_some());
+    let commute = match nearest_workplace {
+        Some(d) => ((d - COMMUTE_FREE_DISTANCE).max(0.0) * COMMUTE_PENALTY_PER_UNIT).min(MAX_COMMUTE_PENALTY),
+        None if has_workplaces => MAX_COMMUTE_PENALTY,
+        None => 0.0,
+    };
+
+    let pollution = spatial_index
+        .query_radius(position, POLLUTION_RADIUS)
+        .map(|entry| match entry.kind {
+            SpatialKind::Industry => POLLUTION_PER_INDUSTRY * (1.0 - distance(position, entry.position) / POLLUTION_RADIUS),
+            SpatialKind::PowerRelay => POLLUTION_PER_RELAY,
+            _ => 0.0,
+        })
+        .sum::<f32>()
+        .min(MAX_POLLUTION_PENALTY);
+
+    (commute, pollution)
+}
+
+/// Local terms of one household, summed over its residents: crowding, each service, commute,
+/// pollution and the adjustment from clamping to 0..=100.
+#[derive(Default)]
+struct LocalTerms {
+    crowding: f32,
+    services: [f32; ALL_SERVICE_TYPES.len()],
+    commute: f32,
+    pollution: f32,
+    clamping: f32,
+}
+
+/// Scores each habitation structure from the colony baseline plus its own crowding, service
+/// coverage, commute and pollution. The colony score is the resident-weighted average, with
+/// anyone not housed scored on the baseline and colony-wide coverage.
+/// Returns the colony score split into its contributing factors.
+pub fn calculate_colony_happiness(
+    game_state: &mut GameState,
+    coverage: &ServiceCoverage,
+    spatial_index: &SpatialIndex,
+) -> HappinessBreakdown {
+    let mut breakdown = colony_baseline(game_state);
+    let baseline: f32 = breakdown.factors.iter().map(|f| f.value).sum();
+
+    let mut household_happiness = HashMap::new();
+    let mut sums = LocalTerms::default();
+    let (mut far_households, mut polluted_households) = (0, 0);
+    let add_household = |sums: &mut LocalTerms, residents: u32, crowding: f32, services: [f32; ALL_SERVICE_TYPES.len()], commute: f32, pollution: f32| {
+        let raw = baseline + crowding + services.iter().sum::<f32>() - commute - pollution;
+        let local = raw.clamp(0.0, 100.0);
+        let weight = residents as f32;
+        sums.crowding += crowding * weight;
+        for (sum, value) in sums.services.iter_mut().zip(services) {
+            *sum += value * weight;
+        }
+        sums.commute += commute * weight;
+        sums.pollution += pollution * weight;
+        sums.clamping += (local - raw) * weight;
+        local
+    };
+
+    let mut housed = 0;
+    for structure in &game_state.habitation_structures {
+        let services = ALL_SERVICE_TYPES.map(|s| service_happiness(coverage.structure_coverage(&structure.id, Some(s))));
+        let (commute, pollution) = structure
+            .position
+            .map_or((0.0, 0.0), |position| location_penalties(game_state, spatial_index, position));
+        if structure.current_inhabitants > 0 {
+            far_households += (commute > 0.0) as u32;
+            polluted_households += (pollution > 0.0) as u32;
         }
+        let local = add_household(&mut sums, structure.current_inhabitants, crowding_happiness(structure), services, commute, pollution);
+        household_happiness.insert(structure.id.clone(), local);
+        housed += structure.current_inhabitants;
     }
 
-    game_state.colony_happiness = happiness_score.clamp(0.0, 100.0);
+    // Everyone not housed, or a stand-in resident for an empty colony, goes by colony-wide coverage.
+    let unhoused = game_state.total_inhabitants.saturating_sub(housed);
+    let residents = housed + unhoused;
+    let services = ALL_SERVICE_TYPES.map(|s| service_happiness(coverage.coverage.get(&s).copied().unwrap_or(0.0)));
+    add_household(&mut sums, if residents == 0 { 1 } else { unhoused }, 0.0, services, 0.0, 0.0);
+
+    let weight = residents.max(1) as f32;
+    let crowding = sums.crowding / weight;
+    if crowding != 0.0 {
+        breakdown.push("Crowding", crowding, "Roomy homes are pleasant; full ones less so");
+    }
+    for (service_type, sum) in ALL_SERVICE_TYPES.iter().zip(sums.services) {
+        let ratio = coverage.coverage.get(service_type).copied().unwrap_or(0.0);
+        breakdown.push(
+            format!("{:?}", service_type),
+            sum / weight,
+            format!("{:.0}% of residents covered", ratio * 100.0),
+        );
+    }
+    if sums.commute > 0.0 {
+        breakdown.push(
+            "Commute",
+            -sums.commute / weight,
+            format!("{} households live over {:.0} from the nearest workplace", far_households, COMMUTE_FREE_DISTANCE),
+        );
+    }
+    if sums.pollution > 0.0 {
+        breakdown.push(
+            "Pollution",
+            -sums.pollution / weight,
+            format!("{} households live near industry or power relays", polluted_households),
+        );
+    }
+    if sums.clamping.abs() > f32::EPSILON {
+        breakdown.push("Limits", sums.clamping / weight, "Household scores are capped between 0 and 100");
+    }
+
+    breakdown.total = breakdown.factors.iter().map(|f| f.value).sum();
+    game_state.colony_happiness = breakdown.total;
+    game_state.household_happiness = household_happiness;
+    breakdown
 }
 
 pub fn happiness_system(
     mut game_state: ResMut<GameState>,
     mut happiness: ResMut<HappinessResource>,
+    mut breakdown: ResMut<HappinessBreakdown>,
     coverage: Res<ServiceCoverage>,
+    spatial_index: Res<SpatialIndex>,
 ) {
-    calculate_colony_happiness(&mut game_state, &coverage);
+    *breakdown = calculate_colony_happiness(&mut game_state, &coverage, &spatial_index);
     happiness.score = game_state.colony_happiness;
-}
\ No newline at end of file
+}
@@ -1,3 +1,4 @@
+pub mod agents;
 pub mod happiness;
 pub mod population;
 pub mod research;
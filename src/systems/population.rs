@@ -1,35 +1,142 @@
 use bevy::prelude::*;
 use crate::{
-    game_state::{GameState, ResourceType},
+    game_state::{self, GameState, ResourceType},
     resources::population::PopulationResource,
 };
 
+/// Births per working-age inhabitant per tick at full happiness.
+pub const BIRTH_RATE: f32 = 0.015;
+/// Share of children who come of age each tick.
+pub const MATURING_RATE: f32 = 0.02;
+/// Share of working-age inhabitants who retire each tick.
+pub const RETIREMENT_RATE: f32 = 0.005;
+/// Share of the elderly who die of old age each tick.
+pub const ELDERLY_DEATH_RATE: f32 = 0.02;
+/// Share of the whole population lost each tick while the colony has no food.
+pub const STARVATION_DEATH_RATE: f32 = 0.05;
+/// Happiness below which inhabitants start thinking about leaving.
+pub const EMIGRATION_HAPPINESS_THRESHOLD: f32 = 30.0;
+/// Consecutive unhappy ticks before anyone actually leaves.
+pub const EMIGRATION_GRACE_TICKS: u32 = 10;
+/// Share of the population that emigrates each tick once the grace period is over.
+pub const EMIGRATION_RATE: f32 = 0.02;
+
+/// Adds `rate` to `progress` and returns the whole number of people it now covers.
+fn accumulate(progress: &mut f32, rate: f32) -> u32 {
+    *progress += rate;
+    let whole = progress.floor() as u32;
+    *progress -= whole as f32;
+    whole
+}
+
 pub fn population_growth_system(
     mut game_state: ResMut<GameState>,
     mut population: ResMut<PopulationResource>,
 ) {
-    population.count = game_state.total_inhabitants;
-
-    let has_housing = population.count < game_state.available_housing_capacity;
-    let food_amount = *game_state
+    let total_inhabitants = game_state.total_inhabitants;
+    let has_housing = total_inhabitants < game_state.available_housing_capacity;
+    let food_amount = game_state
         .current_resources
-        .get(&ResourceType::NutrientPaste)
-        .unwrap_or(&0.0);
+        .get(ResourceType::NutrientPaste);
     let has_food = food_amount > 0.0;
+    let happiness = game_state.colony_happiness;
+    let game_state = &mut *game_state;
+    let demographics = &mut game_state.demographics;
+    demographics.reconcile(total_inhabitants);
+
+    // Ageing
+    let maturing = accumulate(&mut demographics.maturing_progress, demographics.children as f32 * MATURING_RATE)
+        .min(demographics.children);
+    demographics.children -= maturing;
+    demographics.working_age += maturing;
+    let retiring = accumulate(&mut demographics.retiring_progress, demographics.working_age as f32 * RETIREMENT_RATE)
+        .min(demographics.working_age);
+    demographics.working_age -= retiring;
+    demographics.elderly += retiring;
+
+    // Deaths
+    let mut deaths = accumulate(&mut demographics.death_progress, demographics.elderly as f32 * ELDERLY_DEATH_RATE)
+        .min(demographics.elderly);
+    demographics.elderly -= deaths;
+    if !has_food {
+        let population = demographics.total() as f32;
+        let starving = accumulate(&mut demographics.starvation_progress, population * STARVATION_DEATH_RATE);
+        deaths += demographics.remove(starving);
+    }
+
+    // Each household is weighed on its own happiness; anyone without a home goes by the colony score.
+    let household_happiness = &game_state.household_happiness;
+    let local_happiness = |id: &str| household_happiness.get(id).copied().unwrap_or(happiness);
+    let housed: u32 = game_state.habitation_structures.iter().map(|h| h.current_inhabitants).sum();
+    let unhoused = demographics.total().saturating_sub(housed);
+
+    // Emigration
+    let mut emigrants = 0;
+    let unhappy_unhoused = unhoused > 0 && happiness < EMIGRATION_HAPPINESS_THRESHOLD;
+    let any_unhappy = unhappy_unhoused
+        || game_state
+            .habitation_structures
+            .iter()
+            .any(|h| h.current_inhabitants > 0 && local_happiness(&h.id) < EMIGRATION_HAPPINESS_THRESHOLD);
+    if any_unhappy {
+        demographics.unhappy_ticks += 1;
+        if demographics.unhappy_ticks == EMIGRATION_GRACE_TICKS {
+            game_state::add_notification(
+                &mut game_state.notifications,
+                "ALERT: Unhappy inhabitants have begun leaving the colony.".to_string(),
+                0.0,
+            );
+        }
+        if demographics.unhappy_ticks >= EMIGRATION_GRACE_TICKS {
+            for structure in game_state.habitation_structures.iter_mut() {
+                if structure.current_inhabitants == 0 || local_happiness(&structure.id) >= EMIGRATION_HAPPINESS_THRESHOLD {
+                    continue;
+                }
+                let rate = structure.current_inhabitants as f32 * EMIGRATION_RATE;
+                let leaving = accumulate(&mut demographics.emigration_progress, rate).min(structure.current_inhabitants);
+                let left = demographics.remove(leaving);
+                structure.current_inhabitants -= left;
+                emigrants += left;
+            }
+            if unhappy_unhoused {
+                let rate = unhoused as f32 * EMIGRATION_RATE;
+                let leaving = accumulate(&mut demographics.emigration_progress, rate);
+                emigrants += demographics.remove(leaving);
+            }
+        }
+    } else {
+        demographics.unhappy_ticks = 0;
+        demographics.emigration_progress = 0.0;
+    }
 
-    let happiness_factor = (game_state.colony_happiness - 50.0) / 50.0;
-    if has_housing && has_food && happiness_factor > 0.0 {
-        let growth_rate = population.count as f32 * 0.01 * happiness_factor;
-        game_state.population_growth_progress += growth_rate;
-        let added = game_state.population_growth_progress.floor() as u32;
-        if added > 0 {
-            population.count += added;
-            game_state.population_growth_progress -= added as f32;
-            population.count = population
-                .count
-                .min(game_state.available_housing_capacity);
+    // Births, from happy households only
+    let mut births = 0;
+    if has_housing && has_food && demographics.total() > 0 {
+        let working_share = demographics.working_age as f32 / demographics.total() as f32;
+        let mut room = game_state.available_housing_capacity.saturating_sub(demographics.total());
+        let households = game_state
+            .habitation_structures
+            .iter()
+            .map(|h| (h.current_inhabitants, local_happiness(&h.id)))
+            .chain(std::iter::once((unhoused, happiness)));
+        for (residents, local) in households {
+            let happiness_factor = (local - 50.0) / 50.0;
+            if residents == 0 || happiness_factor <= 0.0 {
+                continue;
+            }
+            let growth_rate = residents as f32 * working_share * BIRTH_RATE * happiness_factor;
+            let born = accumulate(&mut game_state.population_growth_progress, growth_rate).min(room);
+            room -= born;
+            births += born;
         }
+        demographics.children += births;
     }
 
+    demographics.births_last_tick = births;
+    demographics.deaths_last_tick = deaths;
+    demographics.emigrants_last_tick = emigrants;
+    population.count = demographics.total();
     game_state.total_inhabitants = population.count;
-}
\ No newline at end of file
+    // Specialists who died, retired or left give up their posts.
+    game_state::release_excess_specialists(game_state);
+}
@@ -1,22 +1,117 @@
 use bevy::prelude::*;
+use std::collections::HashMap;
 
-use crate::game_state::{GameState, Tech};
+use crate::game_state::{
+    add_notification, is_research_active, register_breakthrough, research_remaining, staffing_fraction, tech_eureka, tech_node,
+    BuildingStatus, GameState, ResearchField, Tech, ALL_RESEARCH_FIELDS, ALL_TECHS,
+    RESEARCH_INSTITUTE_CREW, RESEARCH_INSTITUTE_SPECIALISTS, STAT_RESEARCH_RATE,
+};
 
+/// Research points an institute produces per tick with a full crew.
+pub const BASE_RESEARCH_POINTS: f32 = 1.0;
+/// Extra points per tick from each researcher at a crewed institute.
+pub const POINTS_PER_RESEARCHER: f32 = 0.5;
+
+/// Research points each field produces per tick: every crewed, powered institute contributes its
+/// crew's output plus its researchers to its field, then modifiers (Education coverage among them) apply.
+pub fn research_points_per_tick(game_state: &GameState) -> HashMap<ResearchField, f32> {
+    let mut points: HashMap<ResearchField, f32> = ALL_RESEARCH_FIELDS.into_iter().map(|f| (f, 0.0)).collect();
+    for ri in game_state.research_institutes.iter().filter(|ri| ri.status != BuildingStatus::Unpowered) {
+        let crew = staffing_fraction(ri.assigned_workers, RESEARCH_INSTITUTE_CREW);
+        if crew <= 0.0 {
+            continue;
+        }
+        let researchers = ri.assigned_specialists.min(RESEARCH_INSTITUTE_SPECIALISTS);
+        *points.entry(ri.field).or_default() += crew * BASE_RESEARCH_POINTS + researchers as f32 * POINTS_PER_RESEARCHER;
+    }
+    // Modifiers apply to the colony total once, then each field keeps its share of the result.
+    let total: f32 = points.values().sum();
+    if total > 0.0 {
+        let scale = game_state.modifiers.apply(STAT_RESEARCH_RATE, total).max(0.0) / total;
+        for value in points.values_mut() {
+            *value *= scale;
+        }
+    }
+    points
+}
+
+/// Estimated ticks until each queued tech completes, or `None` while a field it needs produces nothing.
+/// Each field works through the queue front to back, so a project waits on the demand ahead of it in
+/// every field it draws on.
+pub fn research_etas(game_state: &GameState, points: &HashMap<ResearchField, f32>) -> HashMap<Tech, Option<u32>> {
+    let mut demand_ahead: HashMap<ResearchField, f32> = HashMap::new();
+    let mut etas = HashMap::new();
+    for tech in &game_state.research_queue {
+        let mut eta = Some(0);
+        for (field, _) in tech_node(*tech).research_costs {
+            let remaining = research_remaining(game_state, *tech, *field);
+            let demand = demand_ahead.entry(*field).or_default();
+            *demand += remaining;
+            if remaining <= 0.0 {
+                continue;
+            }
+            let rate = points.get(field).copied().unwrap_or(0.0);
+            eta = eta.zip((rate > 0.0).then(|| (*demand / rate).ceil() as u32)).map(|(a, b)| a.max(b));
+        }
+        etas.insert(*tech, eta);
+    }
+    etas
+}
+
+/// Claims the eureka boost of every unresearched tech whose milestone the colony has reached.
+/// Boosts apply whether or not the tech is queued yet.
+pub fn eureka_system(mut game_state: ResMut<GameState>) {
+    for tech in ALL_TECHS {
+        if game_state.unlocked_techs.contains(&tech) || game_state.eureka_claimed.contains(&tech) {
+            continue;
+        }
+        let Some(eureka) = tech_eureka(tech) else {
+            continue;
+        };
+        if !(eureka.condition)(&game_state) {
+            continue;
+        }
+        game_state.eureka_claimed.insert(tech);
+        add_notification(
+            &mut game_state.notifications,
+            format!("Eureka! {}: {} research {:.0}% complete.", eureka.description, tech, eureka.share * 100.0),
+            0.0,
+        );
+    }
+}
+
+/// Spends each field's points on the active projects in queue order, then completes any project
+/// whose fields are all paid in.
 pub fn research_system(mut game_state: ResMut<GameState>) {
-    if game_state.research_institutes.iter().all(|ri| !ri.is_staffed) {
+    let points = research_points_per_tick(&game_state);
+    let active: Vec<Tech> =
+        game_state.research_queue.iter().copied().filter(|t| is_research_active(&game_state, *t)).collect();
+    if active.is_empty() {
         return;
     }
-    let mut completed_tech: Option<Tech> = None;
-    if let Some((tech, progress)) = &game_state.research_progress {
-        let required_progress = game_state.tech_costs[tech] as f32;
-        if progress + 1.0 >= required_progress {
-            completed_tech = Some(*tech);
+
+    for (field, mut available) in points {
+        for tech in &active {
+            if available <= 0.0 {
+                break;
+            }
+            let spent = research_remaining(&game_state, *tech, field).min(available);
+            if spent > 0.0 {
+                *game_state.research_progress.entry(*tech).or_default().entry(field).or_default() += spent;
+                available -= spent;
+            }
         }
     }
-    if let Some(tech) = completed_tech {
+
+    for tech in active {
+        let done = tech_node(tech).research_costs.iter().all(|(field, _)| research_remaining(&game_state, tech, *field) <= 0.0);
+        if !done {
+            continue;
+        }
+        game_state.research_queue.retain(|t| *t != tech);
+        game_state.research_progress.remove(&tech);
         game_state.unlocked_techs.insert(tech);
-        game_state.research_progress = None;
-    } else if let Some((_, progress)) = &mut game_state.research_progress {
-        *progress += 1.0;
+        add_notification(&mut game_state.notifications, format!("Research complete: {}.", tech), 0.0);
+        register_breakthrough(&mut game_state, tech);
     }
 }
@@ -1,56 +1,104 @@
 use bevy::prelude::*;
+use std::collections::HashMap;
 
-use crate::game_state::{GameState, ServiceCoverage, ServiceType};
+use crate::game_state::{staffing_fraction, GameState, ServiceCoverage, ALL_SERVICE_TYPES};
+use crate::resources::spatial::{SpatialIndex, SpatialKind};
 
+/// Share of a building's service still delivered at the edge of its radius.
+const EDGE_FALLOFF: f32 = 0.5;
+
+/// Service strength at `distance` from a building: full at the building, `EDGE_FALLOFF` at the
+/// edge of the radius and nothing beyond it.
+fn distance_falloff(distance: f32, radius: f32) -> f32 {
+    if radius <= 0.0 || distance > radius {
+        0.0
+    } else {
+        1.0 - (1.0 - EDGE_FALLOFF) * distance / radius
+    }
+}
+
+/// Computes coverage per habitation structure: each staffed service building divides its capacity
+/// among the residents in range, weighted by distance falloff. Colony-wide coverage is the
+/// population-weighted average, so homeless inhabitants count as uncovered.
+/// Buildings or structures that haven't been placed are treated as co-located.
+/// Structures in reach come from the spatial index, so each building only visits its neighbours.
 pub fn service_coverage_system(
     game_state: Res<GameState>,
+    spatial_index: Res<SpatialIndex>,
     mut coverage: ResMut<ServiceCoverage>,
 ) {
     coverage.coverage.clear();
+    coverage.by_structure.clear();
     let demand = game_state.total_inhabitants;
-    let service_types = [
-        ServiceType::Wellness,
-        ServiceType::Security,
-        ServiceType::Education,
-        ServiceType::Recreation,
-        ServiceType::Spiritual,
-    ];
-
-    for service_type in service_types {
-        if demand == 0 {
-            coverage.coverage.insert(service_type, 1.0);
-            continue;
-        }
+    let structures = &game_state.habitation_structures;
+    let slot_of: HashMap<&str, usize> = structures.iter().enumerate().map(|(i, h)| (h.id.as_str(), i)).collect();
+    let unplaced: Vec<usize> = structures
+        .iter()
+        .enumerate()
+        .filter(|(_, h)| h.position.is_none() || !spatial_index.contains(&h.id))
+        .map(|(i, _)| i)
+        .collect();
 
-        let mut supply = 0;
+    for service_type in ALL_SERVICE_TYPES {
+        // Service delivered to each structure, in residents served
+        let mut delivered = vec![0.0_f32; structures.len()];
         for building in &game_state.service_buildings {
-            if building.service_type == service_type && building.is_active {
-                if let Some(tier) = building.available_tiers.get(building.current_tier_index) {
-                    if building.assigned_specialists >= tier.specialist_requirement {
-                        let in_range = if let Some(b_pos) = building.position {
-                            game_state.habitation_structures.iter().any(|hab| {
-                                if let Some(h_pos) = hab.position {
-                                    let dx = b_pos.0 - h_pos.0;
-                                    let dy = b_pos.1 - h_pos.1;
-                                    let dist2 = dx * dx + dy * dy;
-                                    dist2 <= tier.service_radius * tier.service_radius
-                                } else {
-                                    false
-                                }
-                            })
-                        } else {
-                            true
-                        };
-
-                        if in_range {
-                            supply += tier.service_capacity;
-                        }
-                    }
-                }
+            if building.service_type != service_type || !building.is_active {
+                continue;
+            }
+            let Some(tier) = building.available_tiers.get(building.current_tier_index) else {
+                continue;
+            };
+            let capacity = tier.service_capacity as f32
+                * staffing_fraction(building.assigned_specialists, tier.specialist_requirement);
+            if capacity <= 0.0 {
+                continue;
             }
+
+            // (structure slot, falloff) for every structure this building reaches
+            let in_reach: Vec<(usize, f32)> = match building.position {
+                Some(b_pos) => spatial_index
+                    .query_radius(b_pos, tier.service_radius)
+                    .filter(|entry| entry.kind == SpatialKind::Habitation)
+                    .filter_map(|entry| {
+                        let distance = ((entry.position.0 - b_pos.0).powi(2) + (entry.position.1 - b_pos.1).powi(2)).sqrt();
+                        slot_of.get(entry.id.as_str()).map(|slot| (*slot, distance_falloff(distance, tier.service_radius)))
+                    })
+                    .chain(unplaced.iter().map(|slot| (*slot, 1.0)))
+                    .collect(),
+                None => (0..structures.len()).map(|slot| (slot, 1.0)).collect(),
+            };
+            let total_weight: f32 = in_reach
+                .iter()
+                .map(|(slot, falloff)| structures[*slot].current_inhabitants as f32 * falloff)
+                .sum();
+            if total_weight <= 0.0 {
+                continue;
+            }
+
+            for (slot, falloff) in in_reach {
+                let weight = structures[slot].current_inhabitants as f32 * falloff;
+                // A distant structure can't be served better than the falloff allows, however idle the building.
+                delivered[slot] += (capacity * weight / total_weight).min(weight);
+            }
+        }
+
+        let mut covered = 0.0;
+        for (hab, served) in structures.iter().zip(&delivered) {
+            let ratio = if hab.current_inhabitants > 0 {
+                (served / hab.current_inhabitants as f32).min(1.0)
+            } else {
+                0.0
+            };
+            covered += ratio * hab.current_inhabitants as f32;
+            coverage
+                .by_structure
+                .entry(hab.id.clone())
+                .or_default()
+                .insert(service_type, ratio);
         }
 
-        let ratio = (supply as f32 / demand as f32).min(1.0);
+        let ratio = if demand == 0 { 1.0 } else { (covered / demand as f32).min(1.0) };
         coverage.coverage.insert(service_type, ratio);
     }
-}
\ No newline at end of file
+}
@@ -1,10 +1,13 @@
 use bevy::prelude::*;
-use crate::game_state::{GameState, ServiceCoverage, ServiceType};
+use crate::components::citizen::{Citizen, Home, Needs, PersonalHappiness, Specialist, Workplace};
+use crate::game_state::{self, AgeCohort, GameState, GraphData, ServiceCoverage, ServiceType, UNEMPLOYMENT_TOLERANCE};
+use crate::systems::happiness::HappinessBreakdown;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DiagnosticType {
     NutrientPaste,
     Housing,
+    Employment,
     Healthcare,
     Security,
     Recreation,
@@ -21,9 +24,27 @@ pub(super) struct PopulationStatusText;
 #[derive(Component)]
 pub(super) struct PopulationFactorsText;
 #[derive(Component)]
+pub(super) struct LaborStatsText;
+#[derive(Component)]
+pub(super) struct DemographicsText;
+#[derive(Component)]
+pub(super) struct CohortGraphArea;
+#[derive(Component)]
+pub(super) struct HousingOccupancyText;
+#[derive(Component)]
 pub(super) struct ServiceCoverageItem(pub ServiceType);
 #[derive(Component)]
 pub(super) struct PanelHappinessText;
+#[derive(Component)]
+pub(super) struct HappinessBreakdownText;
+#[derive(Component)]
+pub(super) struct AgentModeButton;
+#[derive(Component)]
+pub(super) struct AgentModeButtonText;
+#[derive(Component)]
+pub(super) struct CitizenStepButton(pub i32);
+#[derive(Component)]
+pub(super) struct CitizenInspectorText;
 
 pub(super) fn build(viewport: &mut ChildBuilder, _assets: &Res<AssetServer>) {
 
@@ -96,11 +117,69 @@ pub(super) fn build(viewport: &mut ChildBuilder, _assets: &Res<AssetServer>) {
                     },
                 )
                 .with_style(Style {
-                    margin: UiRect::bottom(Val::Px(10.0)),
+                    margin: UiRect::bottom(Val::Px(2.0)),
                     ..default()
                 }),
                 PopulationFactorsText,
             ));
+            status.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 14.0,
+                        color: LABEL_TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(2.0)),
+                    ..default()
+                }),
+                LaborStatsText,
+            ));
+            status.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 14.0,
+                        color: LABEL_TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(4.0)),
+                    ..default()
+                }),
+                DemographicsText,
+            ));
+            status.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Px(80.0),
+                        margin: UiRect::bottom(Val::Px(10.0)),
+                        ..default()
+                    },
+                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.3).into(),
+                    ..default()
+                },
+                CohortGraphArea,
+            ));
+            status.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 14.0,
+                        color: LABEL_TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                }),
+                HousingOccupancyText,
+            ));
 
             status.spawn(
                 TextBundle::from_section(
@@ -120,6 +199,7 @@ pub(super) fn build(viewport: &mut ChildBuilder, _assets: &Res<AssetServer>) {
             let diagnostics = [
                 DiagnosticType::NutrientPaste,
                 DiagnosticType::Housing,
+                DiagnosticType::Employment,
                 DiagnosticType::Healthcare,
                 DiagnosticType::Security,
                 DiagnosticType::Recreation,
@@ -199,8 +279,220 @@ pub(super) fn build(viewport: &mut ChildBuilder, _assets: &Res<AssetServer>) {
                 }),
                 PanelHappinessText,
             ));
+            status.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 14.0,
+                        color: LABEL_TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::top(Val::Px(2.0)),
+                    ..default()
+                }),
+                HappinessBreakdownText,
+            ));
+
+            status.spawn(
+                TextBundle::from_section(
+                    "CITIZENS",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: LABEL_TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::vertical(Val::Px(10.0)),
+                    ..default()
+                }),
+            );
+            status
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::bottom(Val::Px(5.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                padding: UiRect::all(Val::Px(5.0)),
+                                margin: UiRect::right(Val::Px(8.0)),
+                                ..default()
+                            },
+                            background_color: NORMAL_BUTTON.into(),
+                            ..default()
+                        },
+                        AgentModeButton,
+                    ))
+                    .with_children(|btn| {
+                        btn.spawn((
+                            TextBundle::from_section(
+                                "Agent Mode: OFF",
+                                TextStyle { font_size: 14.0, color: PRIMARY_TEXT_COLOR, ..default() },
+                            ),
+                            AgentModeButtonText,
+                        ));
+                    });
+                    for (label, step) in [("< Prev", -1), ("Next >", 1)] {
+                        row.spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    padding: UiRect::all(Val::Px(5.0)),
+                                    margin: UiRect::right(Val::Px(4.0)),
+                                    ..default()
+                                },
+                                background_color: NORMAL_BUTTON.into(),
+                                ..default()
+                            },
+                            CitizenStepButton(step),
+                        ))
+                        .with_children(|btn| {
+                            btn.spawn(TextBundle::from_section(
+                                label,
+                                TextStyle { font_size: 14.0, color: PRIMARY_TEXT_COLOR, ..default() },
+                            ));
+                        });
+                    }
+                });
+            status.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 14.0,
+                        color: PRIMARY_TEXT_COLOR,
+                        ..default()
+                    },
+                ),
+                CitizenInspectorText,
+            ));
         });
 }
+
+pub(super) fn draw_cohort_graph_gizmos(
+    mut gizmos: Gizmos,
+    graph_data: Res<GraphData>,
+    graph_area_query: Query<(&Node, &GlobalTransform), With<CohortGraphArea>>,
+) {
+    let history = &graph_data.history;
+    if history.len() < 2 { return; }
+
+    let (graph_node, transform) = if let Ok(result) = graph_area_query.get_single() { result } else { return; };
+    let graph_area = graph_node.size();
+    if graph_area.x <= 0.0 || graph_area.y <= 0.0 { return; }
+
+    let bottom_left = transform.translation().truncate() - graph_area / 2.0;
+    let max_val = history.iter().fold(1u32, |max, stats| max.max(stats.children).max(stats.working_age).max(stats.elderly)) as f32;
+
+    for (color, cohort) in [
+        (Color::PINK, AgeCohort::Children),
+        (Color::LIME_GREEN, AgeCohort::WorkingAge),
+        (Color::SILVER, AgeCohort::Elderly),
+    ] {
+        let points: Vec<Vec2> = history.iter().enumerate().map(|(i, stats)| {
+            let x = bottom_left.x + (i as f32 / (history.len() - 1) as f32) * graph_area.x;
+            let value = match cohort {
+                AgeCohort::Children => stats.children,
+                AgeCohort::WorkingAge => stats.working_age,
+                AgeCohort::Elderly => stats.elderly,
+            } as f32;
+            let y = bottom_left.y + (value / max_val) * graph_area.y;
+            Vec2::new(x, y.clamp(bottom_left.y, bottom_left.y + graph_area.y))
+        }).collect();
+        gizmos.linestrip_2d(points, color);
+    }
+}
+
+pub(super) fn citizen_inspector_button_system(
+    agent_mode_query: Query<&Interaction, (Changed<Interaction>, With<AgentModeButton>)>,
+    step_query: Query<(&Interaction, &CitizenStepButton), (Changed<Interaction>, With<Button>)>,
+    mut selected: ResMut<SelectedCitizen>,
+    mut game_state: ResMut<GameState>,
+) {
+    for interaction in agent_mode_query.iter() {
+        if *interaction == Interaction::Pressed {
+            game_state::toggle_agent_mode(&mut game_state);
+            selected.0 = 0;
+        }
+    }
+    for (interaction, button) in step_query.iter() {
+        if *interaction == Interaction::Pressed {
+            let count = game_state.total_inhabitants.max(1) as i32;
+            selected.0 = (selected.0 as i32 + button.0).rem_euclid(count) as usize;
+        }
+    }
+}
+
+pub(super) fn update_citizen_inspector_system(
+    game_state: Res<GameState>,
+    selected: Res<SelectedCitizen>,
+    citizens: Query<(&Citizen, &Home, &Workplace, &Needs, &PersonalHappiness, Has<Specialist>, Option<&AgeCohort>)>,
+    mut texts: ParamSet<(
+        Query<&mut Text, With<AgentModeButtonText>>,
+        Query<&mut Text, With<CitizenInspectorText>>,
+    )>,
+) {
+    if !game_state.is_changed() && !selected.is_changed() { return; }
+
+    if let Ok(mut text) = texts.p0().get_single_mut() {
+        text.sections[0].value = if game_state.agent_mode { "Agent Mode: ON" } else { "Agent Mode: OFF" }.to_string();
+    }
+
+    let mut inspector_query = texts.p1();
+    let Ok(mut text) = inspector_query.get_single_mut() else { return; };
+    if !game_state.agent_mode {
+        text.sections[0].value = "Enable agent mode to inspect individual colonists.".to_string();
+        return;
+    }
+
+    let mut agents: Vec<_> = citizens.iter().collect();
+    agents.sort_by_key(|(citizen, ..)| **citizen);
+    let Some((citizen, home, workplace, needs, happiness, is_specialist, cohort)) =
+        agents.get(selected.0.min(agents.len().saturating_sub(1)))
+    else {
+        text.sections[0].value = "No colonists yet.".to_string();
+        return;
+    };
+    text.sections[0].value = format!(
+        "Colonist #{} ({}, {}) - {} of {}\nHome: {}\nWorkplace: {}\nNeeds: food {:.0}%, shelter {:.0}%, work {:.0}%, services {:.0}%\nHappiness: {:.0}%",
+        citizen.0,
+        cohort.map_or("Newcomer", |c| c.label()),
+        if *is_specialist { "Specialist" } else { "Inhabitant" },
+        selected.0.min(agents.len() - 1) + 1,
+        agents.len(),
+        home.0.as_deref().unwrap_or("none"),
+        workplace.0.as_deref().unwrap_or("none"),
+        needs.food * 100.0,
+        needs.shelter * 100.0,
+        needs.employment * 100.0,
+        needs.services * 100.0,
+        happiness.0
+    );
+}
+/// Lists each happiness factor with its signed contribution, biggest effects first.
+pub(super) fn update_happiness_breakdown_system(
+    breakdown: Res<HappinessBreakdown>,
+    mut text_query: Query<&mut Text, With<HappinessBreakdownText>>,
+) {
+    if !breakdown.is_changed() { return; }
+    let Ok(mut text) = text_query.get_single_mut() else { return; };
+
+    let mut factors: Vec<_> = breakdown.factors.iter().filter(|f| f.value.abs() >= 0.05).collect();
+    factors.sort_by(|a, b| b.value.abs().total_cmp(&a.value.abs()));
+    let lines: Vec<String> = factors
+        .iter()
+        .map(|f| format!("{:+.1} {}: {}", f.value, f.label, f.reason))
+        .collect();
+    text.sections[0].value = format!("Why {:.0}%:\n{}", breakdown.total, lines.join("\n"));
+}
+
 pub(super) fn update_colony_status_panel_system(
     game_state: Res<GameState>,
     coverage: Res<ServiceCoverage>,
@@ -210,6 +502,9 @@ pub(super) fn update_colony_status_panel_system(
         Query<&mut Text, With<PopulationFactorsText>>,
         Query<(&mut Text, &ServiceCoverageItem)>,
         Query<&mut Text, With<PanelHappinessText>>,
+        Query<&mut Text, With<LaborStatsText>>,
+        Query<&mut Text, With<DemographicsText>>,
+        Query<&mut Text, With<HousingOccupancyText>>,
     )>,
 ) {
     if !game_state.is_changed() { return; }
@@ -251,6 +546,63 @@ pub(super) fn update_colony_status_panel_system(
         }
     }
 
+    if let Ok(mut labor_text) = queries.p5().get_single_mut() {
+        let labor = &game_state.labor_market;
+        labor_text.sections[0].value = format!(
+            "Workers {} | Specialists {} | Unemployed {} ({:.0}%) | Homeless {} | Jobs {} ({} worker, {} specialist)",
+            labor.employed_workers,
+            labor.assigned_specialists,
+            labor.unemployed,
+            labor.unemployment_rate() * 100.0,
+            labor.homeless,
+            labor.total_jobs(),
+            labor.worker_jobs,
+            labor.specialist_jobs
+        );
+        labor_text.sections[0].style.color = if labor.homeless > 0 || labor.unemployment_rate() > UNEMPLOYMENT_TOLERANCE {
+            Color::ORANGE
+        } else {
+            LABEL_TEXT_COLOR
+        };
+    }
+
+    if let Ok(mut demographics_text) = queries.p6().get_single_mut() {
+        let demographics = &game_state.demographics;
+        let cohorts: Vec<String> = [AgeCohort::Children, AgeCohort::WorkingAge, AgeCohort::Elderly]
+            .iter()
+            .map(|c| format!("{} {}", c.label(), demographics.cohort(*c)))
+            .collect();
+        demographics_text.sections[0].value = format!(
+            "{} | Last tick: +{} born, -{} died, -{} emigrated",
+            cohorts.join(" | "),
+            demographics.births_last_tick,
+            demographics.deaths_last_tick,
+            demographics.emigrants_last_tick
+        );
+        demographics_text.sections[0].style.color = if demographics.deaths_last_tick + demographics.emigrants_last_tick > demographics.births_last_tick {
+            Color::ORANGE
+        } else {
+            LABEL_TEXT_COLOR
+        };
+    }
+
+    if let Ok(mut housing_text) = queries.p7().get_single_mut() {
+        let lines: Vec<String> = game_state
+            .habitation_structures
+            .iter()
+            .filter_map(|structure| {
+                structure.available_tiers.get(structure.tier_index).map(|tier| {
+                    format!("{} {}: {}/{} residents", tier.name, structure.id, structure.current_inhabitants, tier.housing_capacity)
+                })
+            })
+            .collect();
+        housing_text.sections[0].value = if lines.is_empty() {
+            "No habitation structures.".to_string()
+        } else {
+            format!("Housing occupancy:\n{}", lines.join("\n"))
+        };
+    }
+
     for (mut text, item) in queries.p0().iter_mut() {
         let (status_text, color) = match item.0 {
             DiagnosticType::NutrientPaste => {
@@ -262,6 +614,16 @@ pub(super) fn update_colony_status_panel_system(
                 let status = ratio < 1.0;
                 (if status { "Adequate" } else { "Overcrowded" }, if status { Color::GREEN } else { Color::RED })
             },
+            DiagnosticType::Employment => {
+                let rate = game_state.labor_market.unemployment_rate();
+                if rate > UNEMPLOYMENT_TOLERANCE {
+                    ("High Unemployment", Color::RED)
+                } else if rate > 0.0 {
+                    ("Some Unemployment", Color::YELLOW)
+                } else {
+                    ("Full Employment", Color::GREEN)
+                }
+            },
             DiagnosticType::Healthcare => ("Nominal", Color::GREEN),
             DiagnosticType::Security => ("Nominal", Color::GREEN),
             DiagnosticType::Recreation => ("Nominal", Color::GREEN),
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use crate::game_state::{self, add_bio_dome, add_extractor, add_power_relay, add_research_institute, add_storage_silo, GameState, ServiceType, ZoneType, BuildingType as GameBuildingType, DevelopmentPhase};
+use crate::resources::spatial::{SpatialIndex, SpatialKind};
 use super::*;
 
 #[derive(Component)]
@@ -41,6 +42,7 @@ fn get_building_metadata() -> HashMap<GameBuildingType, BuildingMetadata> {
     meta.insert(GameBuildingType::ResearchInstitute, BuildingMetadata { name: "Research Institute", category: ConstructionCategory::Operations, required_tech: Some(Tech::BasicConstructionProtocols), required_dp: None, workforce_required: 15 });
     meta.insert(GameBuildingType::Fabricator, BuildingMetadata { name: "Fabricator", category: ConstructionCategory::Operations, required_tech: Some(Tech::BasicConstructionProtocols), required_dp: Some(DevelopmentPhase::DP2), workforce_required: 20 });
     meta.insert(GameBuildingType::ProcessingPlant, BuildingMetadata { name: "Processing Plant", category: ConstructionCategory::Operations, required_tech: Some(Tech::BasicConstructionProtocols), required_dp: Some(DevelopmentPhase::DP2), workforce_required: 20 });
+    meta.insert(GameBuildingType::Spaceport, BuildingMetadata { name: "Spaceport", category: ConstructionCategory::Operations, required_tech: Some(Tech::BasicConstructionProtocols), required_dp: None, workforce_required: 0 });
     meta
 }
 
@@ -53,6 +55,7 @@ fn tag_for_building(bt: GameBuildingType) -> &'static str {
         GameBuildingType::ResearchInstitute => "build_menu.research_institute",
         GameBuildingType::Fabricator => "build_menu.fabricator",
         GameBuildingType::ProcessingPlant => "build_menu.processing_plant",
+        GameBuildingType::Spaceport => "build_menu.spaceport",
     }
 }
 
@@ -81,6 +84,7 @@ pub(super) fn build(viewport: &mut ChildBuilder, _assets: &Res<AssetServer>) {
 pub(super) fn construction_interaction_system(
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<ConfirmBuildButton>)>,
     selected_building: Res<SelectedBuilding>,
+    mut spatial_index: ResMut<SpatialIndex>,
     mut game_state: ResMut<GameState>,
     time: Res<Time>,
 ) {
@@ -88,24 +92,39 @@ pub(super) fn construction_interaction_system(
         if let Ok(Interaction::Pressed) = interaction_query.get_single() {
              let costs = game_state.building_costs.get(&building_type).unwrap().clone();
 
-             let can_afford = costs.iter().all(|(res, &req)| game_state.current_resources.get(res).unwrap_or(&0.0) >= &req);
-             if !can_afford {
+             let state = &mut *game_state;
+             if state.resource_accounts.withdraw(&mut state.current_resources, &costs, "Construction").is_err() {
                  game_state::add_notification(&mut game_state.notifications, format!("Insufficient materials for {:?}.", building_type), time.elapsed_seconds_f64());
                  return;
              }
 
-             for (res, cost) in &costs {
-                 *game_state.current_resources.get_mut(res).unwrap() -= cost;
-             }
-
              match building_type {
-                GameBuildingType::Extractor => add_extractor(&mut game_state),
+                GameBuildingType::Extractor => {
+                    let site = spatial_index.next_free_site();
+                    let id = add_extractor(&mut game_state, Some(site));
+                    spatial_index.upsert(&id, SpatialKind::Industry, site);
+                }
                 GameBuildingType::BioDome => add_bio_dome(&mut game_state),
-                GameBuildingType::PowerRelay => add_power_relay(&mut game_state),
+                GameBuildingType::PowerRelay => {
+                    let site = spatial_index.next_free_site();
+                    let id = add_power_relay(&mut game_state, Some(site));
+                    spatial_index.upsert(&id, SpatialKind::PowerRelay, site);
+                }
                 GameBuildingType::ResearchInstitute => add_research_institute(&mut game_state),
-                GameBuildingType::Fabricator => game_state::add_fabricator(&mut game_state, 0),
-                GameBuildingType::ProcessingPlant => game_state::add_processing_plant(&mut game_state, 0),
+                GameBuildingType::Fabricator => {
+                    let site = spatial_index.next_free_site();
+                    if let Some(id) = game_state::add_fabricator(&mut game_state, 0, Some(site)) {
+                        spatial_index.upsert(&id, SpatialKind::Industry, site);
+                    }
+                }
+                GameBuildingType::ProcessingPlant => {
+                    let site = spatial_index.next_free_site();
+                    if let Some(id) = game_state::add_processing_plant(&mut game_state, 0, Some(site)) {
+                        spatial_index.upsert(&id, SpatialKind::Industry, site);
+                    }
+                }
                 GameBuildingType::StorageSilo => add_storage_silo(&mut game_state),
+                GameBuildingType::Spaceport => game_state::add_spaceport(&mut game_state),
              }
              game_state::add_notification(&mut game_state.notifications, format!("Construction started: {:?}", building_type), time.elapsed_seconds_f64());
         }
@@ -128,15 +147,17 @@ pub(super) fn zone_construction_system(
 
 pub(super) fn service_construction_system(
     mut interaction_query: Query<(&Interaction, &ConstructServiceButton), (Changed<Interaction>, With<Button>)>,
+    mut spatial_index: ResMut<SpatialIndex>,
     mut game_state: ResMut<GameState>,
 ) {
     for (interaction, button_data) in interaction_query.iter_mut() {
         if *interaction == Interaction::Pressed {
             let service_type = button_data.0;
             let tier_index = button_data.1;
-            // Assuming add_service_building might take position as an Option in the future.
-            // For now, it's not used as per game_state.rs, but good to keep in mind.
-            game_state::add_service_building(&mut game_state, service_type, tier_index, None);
+            let site = spatial_index.next_free_site();
+            if let Some(id) = game_state::add_service_building(&mut game_state, service_type, tier_index, Some(site)) {
+                spatial_index.upsert(&id, SpatialKind::Service(service_type), site);
+            }
             // Consider adding a notification here if desired, similar to other construction systems
         }
     }
@@ -366,12 +387,16 @@ pub(super) fn update_construction_list_system(
 
 pub(super) fn habitation_construction_system(
     mut interaction_query: Query<(&Interaction, &ConstructHabitationButton)>,
+    mut spatial_index: ResMut<SpatialIndex>,
     mut game_state: ResMut<GameState>,
 ) {
     for (interaction, button) in interaction_query.iter_mut() {
         if *interaction == Interaction::Pressed {
             let tier_index = button.0;
-            game_state::add_habitation_structure(&mut game_state, tier_index, None);
+            let site = spatial_index.next_free_site();
+            if let Some(id) = game_state::add_habitation_structure(&mut game_state, tier_index, Some(site)) {
+                spatial_index.upsert(&id, SpatialKind::Habitation, site);
+            }
         }
     }
 }
@@ -383,9 +408,7 @@ pub(super) fn construction_item_interaction_system(
     mut button_query: Query<(&Interaction, &ConstructionItemButton, &mut BackgroundColor), With<Button>>,
 ) {
     let can_afford = |bt: GameBuildingType| -> bool {
-         game_state.building_costs.get(&bt).map_or(true, |costs| {
-            costs.iter().all(|(res, &req)| game_state.current_resources.get(res).unwrap_or(&0.0) >= &req)
-        })
+         game_state.building_costs.get(&bt).map_or(true, |costs| game_state.current_resources.can_afford(costs))
     };
 
     let meta_map = get_building_metadata();
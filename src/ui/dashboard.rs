@@ -1,12 +1,15 @@
 use bevy::prelude::*;
-use crate::game_state::{self, construct_legacy_structure, get_legacy_structure_tiers, upgrade_legacy_structure, ColonyStats, GameState, GraphData, LoadGameEvent, SaveGameEvent, ZoneType};
+use crate::game_state::{self, construct_legacy_structure, get_legacy_structure_tiers, upgrade_legacy_structure, BuildingStatus, ColonyStats, GameState, GraphData, LoadGameEvent, NotificationCommand, SaveGameEvent, UpkeepCategory, ZoneType};
 use super::*;
+use crate::resources::spatial::SpatialIndex;
 
 #[derive(Component)]
 pub(super) struct DashboardPanel;
 #[derive(Component)]
 pub(super) struct NotificationsPanel;
 #[derive(Component)]
+pub(super) struct NotificationActionButton(pub NotificationCommand);
+#[derive(Component)]
 pub(super) struct AnalyticsGraphPanel;
 #[derive(Component)]
 pub(super) struct GraphArea;
@@ -51,6 +54,16 @@ pub(super) struct AssignSpecialistToServiceBuildingButton(pub String);
 #[derive(Component)]
 pub(super) struct UnassignSpecialistFromServiceBuildingButton(pub String);
 #[derive(Component)]
+pub(super) struct UpgradeFabricatorButton(pub String);
+#[derive(Component)]
+pub(super) struct UpkeepPriorityPanel;
+#[derive(Component)]
+pub(super) struct BuildingStatusPanel;
+#[derive(Component)]
+pub(super) struct UpkeepPriorityButton(pub UpkeepCategory, pub bool);
+#[derive(Component)]
+pub(super) struct MothballButton(pub String);
+#[derive(Component)]
 pub(super) struct SaveGameButton;
 #[derive(Component)]
 pub(super) struct LoadGameButton;
@@ -183,7 +196,7 @@ pub(super) fn update_managed_structures_panel_system(
 
                                 details_parent.spawn(
                                     TextBundle::from_section(
-                                        format!("Upkeep: {} Cr/cycle", current_tier.upkeep_cost),
+                                        format!("Upkeep: {} Cr/cycle", game_state.modifiers.upkeep(UpkeepCategory::Zones, current_tier.upkeep_cost)),
                                         TextStyle { font_size: 14.0, color: LABEL_TEXT_COLOR, ..default() },
                                     )
                                     .with_style(Style { margin: UiRect::bottom(Val::Px(8.0)), ..default() }),
@@ -245,6 +258,9 @@ pub(super) fn update_managed_structures_panel_system(
                                         ));
                                     });
 
+                                spawn_status_line(details_parent, zone.status);
+                                spawn_mothball_button(details_parent, &zone.id, zone.is_mothballed);
+
                                 details_parent
                                     .spawn(NodeBundle { style: Style { height: Val::Px(10.0), ..default() }, ..default() });
 
@@ -396,7 +412,7 @@ pub(super) fn update_managed_structures_panel_system(
 
                                 details_parent.spawn(
                                     TextBundle::from_section(
-                                        format!("Upkeep: {} Cr/cycle", current_tier.upkeep_cost),
+                                        format!("Upkeep: {} Cr/cycle", game_state.modifiers.upkeep(UpkeepCategory::ServiceBuildings, current_tier.upkeep_cost)),
                                         TextStyle { font_size: 14.0, color: LABEL_TEXT_COLOR, ..default() },
                                     )
                                     .with_style(Style { margin: UiRect::bottom(Val::Px(8.0)), ..default() }),
@@ -453,6 +469,9 @@ pub(super) fn update_managed_structures_panel_system(
                                         ));
                                     });
 
+                                spawn_status_line(details_parent, building.status);
+                                spawn_mothball_button(details_parent, &building.id, building.is_mothballed);
+
                                 details_parent.spawn(NodeBundle { style: Style { height: Val::Px(10.0), ..default() }, ..default() });
 
                                 let can_assign_more = building.assigned_specialists < current_tier.specialist_requirement;
@@ -505,12 +524,230 @@ pub(super) fn update_managed_structures_panel_system(
                     }
                 }
 
+                // --- Fabricators Section ---
+                parent.spawn(
+                    TextBundle::from_section(
+                        "Managed Fabricators",
+                        TextStyle { font_size: 16.0, color: LABEL_TEXT_COLOR, ..default() },
+                    )
+                    .with_style(Style { margin: UiRect::vertical(Val::Px(10.0)), ..default() }),
+                );
+
+                if game_state.fabricators.is_empty() {
+                    parent.spawn(TextBundle::from_section(
+                        "No fabricators.",
+                        TextStyle { font_size: 14.0, color: PRIMARY_TEXT_COLOR, ..default() },
+                    ));
+                }
+                for fab in game_state.fabricators.iter() {
+                    let Some(tier) = fab.available_tiers.get(fab.tier_index) else {
+                        continue;
+                    };
+                    parent.spawn(TextBundle::from_section(
+                        format!("{} (Staff: {}/{})", tier.name, fab.assigned_specialists, tier.specialist_requirement),
+                        TextStyle { font_size: 14.0, color: PRIMARY_TEXT_COLOR, ..default() },
+                    ));
+                    let Some(next_tier) = fab.available_tiers.get(fab.tier_index + 1) else {
+                        continue;
+                    };
+                    let missing_tech = next_tier.required_tech.filter(|t| !game_state.unlocked_techs.contains(t));
+                    let can_upgrade =
+                        missing_tech.is_none() && game_state.credits >= next_tier.construction_credits_cost as f64;
+                    let label = match missing_tech {
+                        Some(tech) => format!("Upgrade to {} (requires {})", next_tier.name, tech),
+                        None => format!("Upgrade to {} ({} Cr)", next_tier.name, next_tier.construction_credits_cost),
+                    };
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Percent(100.0),
+                                    padding: UiRect::all(Val::Px(5.0)),
+                                    margin: UiRect::bottom(Val::Px(4.0)),
+                                    ..default()
+                                },
+                                background_color: if can_upgrade { NORMAL_BUTTON.into() } else { DISABLED_BUTTON.into() },
+                                ..default()
+                            },
+                            UpgradeFabricatorButton(fab.id.clone()),
+                        ))
+                        .with_children(|btn| {
+                            btn.spawn(TextBundle::from_section(
+                                label,
+                                TextStyle { font_size: 14.0, color: PRIMARY_TEXT_COLOR, ..default() },
+                            ));
+                        });
+                }
             });
         }
     }
 }
 
 
+fn status_icon(status: BuildingStatus) -> (&'static str, Color) {
+    match status {
+        BuildingStatus::Operational => ("✅", Color::LIME_GREEN),
+        BuildingStatus::Unpowered => ("⚡", Color::YELLOW),
+        BuildingStatus::Understaffed => ("👤", Color::ORANGE),
+        BuildingStatus::MissingInputs => ("📦", Color::ORANGE),
+        BuildingStatus::OutputStorageFull => ("⛔", Color::YELLOW),
+        BuildingStatus::UnpaidUpkeep => ("💰", Color::TOMATO),
+        BuildingStatus::Mothballed => ("💤", Color::GRAY),
+        BuildingStatus::UnderConstruction => ("🚧", Color::CYAN),
+    }
+}
+
+fn spawn_status_line(parent: &mut ChildBuilder, status: BuildingStatus) {
+    let (icon, color) = status_icon(status);
+    parent.spawn(TextBundle::from_section(
+        format!("{} {}", icon, status.label()),
+        TextStyle { font_size: 14.0, color, ..default() },
+    ).with_style(Style { margin: UiRect::top(Val::Px(4.0)), ..default() }));
+}
+
+pub(super) fn update_building_status_panel_system(
+    current_app: Res<CurrentApp>,
+    game_state: Res<GameState>,
+    panel_query: Query<Entity, With<BuildingStatusPanel>>,
+    mut commands: Commands,
+) {
+    if current_app.0 != AppType::Dashboard { return; }
+    if !game_state.is_changed() && !current_app.is_changed() { return; }
+
+    if let Ok(panel_entity) = panel_query.get_single() {
+        commands.entity(panel_entity).despawn_descendants();
+        let statuses = game_state::building_statuses(&game_state);
+
+        commands.entity(panel_entity).with_children(|parent| {
+            parent.spawn(TextBundle::from_section("Building Status", TextStyle{font_size: 16.0, color: PRIMARY_TEXT_COLOR, ..default()}));
+            if statuses.is_empty() {
+                parent.spawn(TextBundle::from_section("No buildings yet.", TextStyle { font_size: 12.0, color: LABEL_TEXT_COLOR, ..default() }));
+            }
+            for status in [
+                BuildingStatus::Operational,
+                BuildingStatus::Unpowered,
+                BuildingStatus::Understaffed,
+                BuildingStatus::MissingInputs,
+                BuildingStatus::OutputStorageFull,
+                BuildingStatus::UnpaidUpkeep,
+                BuildingStatus::Mothballed,
+                BuildingStatus::UnderConstruction,
+            ] {
+                let count = statuses.iter().filter(|(_, s)| *s == status).count();
+                if count == 0 { continue; }
+                let (icon, color) = status_icon(status);
+                parent.spawn(TextBundle::from_section(
+                    format!("{} {}: {}", icon, status.label(), count),
+                    TextStyle { font_size: 14.0, color, ..default() },
+                ));
+            }
+        });
+    }
+}
+
+fn spawn_mothball_button(parent: &mut ChildBuilder, building_id: &str, is_mothballed: bool) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    margin: UiRect::top(Val::Px(4.0)),
+                    ..default()
+                },
+                background_color: NORMAL_BUTTON.into(),
+                ..default()
+            },
+            MothballButton(building_id.to_string()),
+        ))
+        .with_children(|btn| {
+            btn.spawn(TextBundle::from_section(
+                if is_mothballed { "Reactivate" } else { "Mothball" },
+                TextStyle { font_size: 14.0, color: if is_mothballed { Color::LIME_GREEN } else { Color::ORANGE }, ..default() },
+            ));
+        });
+}
+
+pub(super) fn update_upkeep_priority_panel_system(
+    current_app: Res<CurrentApp>,
+    game_state: Res<GameState>,
+    panel_query: Query<Entity, With<UpkeepPriorityPanel>>,
+    mut commands: Commands,
+) {
+    if current_app.0 != AppType::Dashboard { return; }
+    if !game_state.is_changed() && !current_app.is_changed() { return; }
+
+    if let Ok(panel_entity) = panel_query.get_single() {
+        commands.entity(panel_entity).despawn_descendants();
+        let budget = game_state::project_budget(&game_state);
+
+        commands.entity(panel_entity).with_children(|parent| {
+            parent.spawn(TextBundle::from_section("Upkeep Priority", TextStyle{font_size: 16.0, color: PRIMARY_TEXT_COLOR, ..default()}));
+
+            for (rank, category) in game_state.upkeep_priority.iter().enumerate() {
+                let upkeep = match category {
+                    UpkeepCategory::Fabricators => budget.fabricator_upkeep,
+                    UpkeepCategory::ProcessingPlants => budget.processing_plant_upkeep,
+                    UpkeepCategory::Zones => budget.zone_upkeep,
+                    UpkeepCategory::ServiceBuildings => budget.service_upkeep,
+                };
+                parent.spawn(NodeBundle { style: Style { flex_direction: FlexDirection::Row, align_items: AlignItems::Center, margin: UiRect::top(Val::Px(2.0)), ..default() }, ..default() })
+                    .with_children(|row| {
+                        for (raise, label) in [(true, "Up"), (false, "Dn")] {
+                            row.spawn((
+                                ButtonBundle {
+                                    style: Style { padding: UiRect::horizontal(Val::Px(4.0)), margin: UiRect::right(Val::Px(2.0)), ..default() },
+                                    background_color: NORMAL_BUTTON.into(),
+                                    ..default()
+                                },
+                                UpkeepPriorityButton(*category, raise),
+                            )).with_children(|btn| {
+                                btn.spawn(TextBundle::from_section(label, TextStyle { font_size: 12.0, color: PRIMARY_TEXT_COLOR, ..default() }));
+                            });
+                        }
+                        row.spawn(TextBundle::from_section(
+                            format!("{}. {} ({:.0} Cr)", rank + 1, category.label(), upkeep),
+                            TextStyle { font_size: 14.0, color: LABEL_TEXT_COLOR, ..default() },
+                        ));
+                    });
+            }
+
+            for fab in &game_state.fabricators {
+                parent.spawn(TextBundle::from_section(format!("Fabricator {}", fab.id), TextStyle { font_size: 12.0, color: LABEL_TEXT_COLOR, ..default() }).with_style(Style { margin: UiRect::top(Val::Px(4.0)), ..default() }));
+                spawn_status_line(parent, fab.status);
+                spawn_mothball_button(parent, &fab.id, fab.is_mothballed);
+            }
+            for plant in &game_state.processing_plants {
+                parent.spawn(TextBundle::from_section(format!("Processing Plant {}", plant.id), TextStyle { font_size: 12.0, color: LABEL_TEXT_COLOR, ..default() }).with_style(Style { margin: UiRect::top(Val::Px(4.0)), ..default() }));
+                spawn_status_line(parent, plant.status);
+                spawn_mothball_button(parent, &plant.id, plant.is_mothballed);
+            }
+        });
+    }
+}
+
+pub(super) fn upkeep_priority_button_system(
+    mut interaction_query: Query<(&Interaction, &UpkeepPriorityButton), (Changed<Interaction>, With<Button>)>,
+    mut game_state: ResMut<GameState>,
+) {
+    for (interaction, button) in interaction_query.iter_mut() {
+        if *interaction == Interaction::Pressed {
+            game_state::move_upkeep_priority(&mut game_state, button.0, button.1);
+        }
+    }
+}
+
+pub(super) fn mothball_button_system(
+    mut interaction_query: Query<(&Interaction, &MothballButton), (Changed<Interaction>, With<Button>)>,
+    mut game_state: ResMut<GameState>,
+) {
+    for (interaction, button) in interaction_query.iter_mut() {
+        if *interaction == Interaction::Pressed {
+            game_state::toggle_mothball(&mut game_state, &button.0);
+        }
+    }
+}
+
 pub(super) fn assign_specialist_to_zone_button_interaction_system(
     mut interaction_query: Query<(&Interaction, &AssignSpecialistToZoneButton), (Changed<Interaction>, With<Button>)>,
     mut game_state: ResMut<GameState>,
@@ -584,14 +821,27 @@ pub(super) fn upgrade_service_building_button_interaction_system(
     }
 }
 
+pub(super) fn upgrade_fabricator_button_interaction_system(
+    interaction_query: Query<(&Interaction, &UpgradeFabricatorButton), (Changed<Interaction>, With<Button>)>,
+    mut game_state: ResMut<GameState>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            game_state::upgrade_fabricator(&mut game_state, &button.0);
+        }
+    }
+}
+
 pub(super) fn remove_service_building_button_interaction_system(
     mut interaction_query: Query<(&Interaction, &RemoveServiceBuildingButton), (Changed<Interaction>, With<Button>)>,
     mut game_state: ResMut<GameState>,
+    mut spatial_index: ResMut<SpatialIndex>,
     mut selected: ResMut<SelectedServiceBuilding>,
 ) {
     for (interaction, button) in interaction_query.iter_mut() {
         if *interaction == Interaction::Pressed {
             game_state::remove_service_building(&mut game_state, &button.0);
+            spatial_index.remove(&button.0);
             selected.0 = None;
         }
     }
@@ -702,6 +952,30 @@ pub(super) fn build(viewport: &mut ChildBuilder, _assets: &Res<AssetServer>) {
                                 ..default()
                             }, LegacyStructurePanel));
 
+                            right_col.spawn((NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Column,
+                                    margin: UiRect::top(Val::Px(10.0)),
+                                    padding: UiRect::all(Val::Px(5.0)),
+                                    border: UiRect::all(Val::Px(1.0)),
+                                    ..default()
+                                },
+                                border_color: BORDER_COLOR.into(),
+                                ..default()
+                            }, UpkeepPriorityPanel));
+
+                            right_col.spawn((NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Column,
+                                    margin: UiRect::top(Val::Px(10.0)),
+                                    padding: UiRect::all(Val::Px(5.0)),
+                                    border: UiRect::all(Val::Px(1.0)),
+                                    ..default()
+                                },
+                                border_color: BORDER_COLOR.into(),
+                                ..default()
+                            }, BuildingStatusPanel));
+
                             // Add ManagedStructuresPanel here
                             right_col.spawn((
                                 NodeBundle {
@@ -736,7 +1010,10 @@ pub(super) fn update_dashboard_notifications_system(
             commands.entity(panel_entity).despawn_descendants();
             commands.entity(panel_entity).with_children(|parent| {
                 parent.spawn(TextBundle::from_section("EVENT LOG", TextStyle{font_size: 18.0, color: LABEL_TEXT_COLOR, ..default()}));
-                for event in game_state.notifications.iter().take(5) {
+                // Entries waiting on a choice stay pinned above the latest five until acted on.
+                let pending = game_state.notifications.iter().filter(|e| !e.actions.is_empty());
+                let recent = game_state.notifications.iter().filter(|e| e.actions.is_empty()).take(5);
+                for event in pending.chain(recent) {
                     let color = if event.message.starts_with("ALERT:") {
                         ALERT_TEXT_COLOR
                     } else {
@@ -746,12 +1023,41 @@ pub(super) fn update_dashboard_notifications_system(
                         format!("[{:.1}] {}", event.timestamp, event.message),
                         TextStyle { font_size: 14.0, color, ..default() }
                     ).with_style(Style{ margin: UiRect::top(Val::Px(4.0)), ..default()}));
+                    if !event.actions.is_empty() {
+                        parent.spawn(NodeBundle { style: Style { flex_direction: FlexDirection::Row, ..default() }, ..default() })
+                            .with_children(|row| {
+                                for action in &event.actions {
+                                    row.spawn((
+                                        ButtonBundle {
+                                            style: Style { padding: UiRect::horizontal(Val::Px(6.0)), margin: UiRect::right(Val::Px(4.0)), ..default() },
+                                            background_color: NORMAL_BUTTON.into(),
+                                            ..default()
+                                        },
+                                        NotificationActionButton(action.command.clone()),
+                                    ))
+                                    .with_children(|btn| {
+                                        btn.spawn(TextBundle::from_section(action.label.clone(), TextStyle { font_size: 14.0, color: PRIMARY_TEXT_COLOR, ..default() }));
+                                    });
+                                }
+                            });
+                    }
                 }
             });
         }
     }
 }
 
+pub(super) fn notification_action_button_system(
+    interaction_query: Query<(&Interaction, &NotificationActionButton), (Changed<Interaction>, With<Button>)>,
+    mut game_state: ResMut<GameState>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            game_state::run_notification_command(&mut game_state, &button.0);
+        }
+    }
+}
+
 pub(super) fn update_admin_spire_panel_system(
     game_state: Res<GameState>,
     panel_query: Query<Entity, With<AdminSpireInfoPanel>>,
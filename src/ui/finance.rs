@@ -0,0 +1,396 @@
+use bevy::prelude::*;
+use crate::game_state::{
+    self, describe_ledger_source, get_loan_offers, project_budget, tax_happiness_penalty, BankruptcyStage,
+    FinancialStatus, GameState, TaxCategory, CREDIT_LIMIT, MAX_ACTIVE_LOANS,
+};
+use super::*;
+
+#[derive(Component)]
+pub(super) struct FinancePanel;
+#[derive(Component)]
+pub(super) struct BudgetContentPanel;
+#[derive(Component)]
+pub(super) struct LedgerGraphArea;
+#[derive(Component)]
+pub(super) struct CostCentersPanel;
+#[derive(Component)]
+pub(super) struct TaxRateButton(pub TaxCategory, pub bool);
+#[derive(Component)]
+pub(super) struct TakeLoanButton(pub usize);
+#[derive(Component)]
+pub(super) struct RepayLoanButton(pub String);
+
+pub(super) fn build(viewport: &mut ChildBuilder, _assets: &Res<AssetServer>) {
+    viewport
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            FinancePanel,
+        ))
+        .with_children(|finance| {
+            finance.spawn(
+                TextBundle::from_section(
+                    "COLONY BUDGET",
+                    TextStyle {
+                        font_size: 28.0,
+                        color: BORDER_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                }),
+            );
+
+            finance
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        flex_grow: 1.0,
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|columns| {
+                    columns.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(55.0),
+                                border: UiRect::all(Val::Px(1.0)),
+                                padding: UiRect::all(Val::Px(10.0)),
+                                flex_direction: FlexDirection::Column,
+                                ..default()
+                            },
+                            border_color: BORDER_COLOR.into(),
+                            ..default()
+                        },
+                        BudgetContentPanel,
+                    ));
+
+                    columns
+                        .spawn(NodeBundle {
+                            style: Style {
+                                width: Val::Percent(45.0),
+                                border: UiRect::all(Val::Px(1.0)),
+                                padding: UiRect::all(Val::Px(10.0)),
+                                flex_direction: FlexDirection::Column,
+                                ..default()
+                            },
+                            border_color: BORDER_COLOR.into(),
+                            ..default()
+                        })
+                        .with_children(|ledger| {
+                            section_header(ledger, "INCOME VS EXPENSES");
+                            ledger.spawn(TextBundle::from_sections([
+                                TextSection::new("Income ", TextStyle { font_size: 14.0, color: Color::GOLD, ..default() }),
+                                TextSection::new("Expenses", TextStyle { font_size: 14.0, color: Color::TOMATO, ..default() }),
+                            ]));
+                            ledger.spawn((
+                                NodeBundle {
+                                    style: Style {
+                                        width: Val::Percent(100.0),
+                                        height: Val::Px(160.0),
+                                        margin: UiRect::vertical(Val::Px(6.0)),
+                                        ..default()
+                                    },
+                                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.3).into(),
+                                    ..default()
+                                },
+                                LedgerGraphArea,
+                            ));
+                            ledger.spawn((
+                                NodeBundle {
+                                    style: Style { flex_direction: FlexDirection::Column, ..default() },
+                                    ..default()
+                                },
+                                CostCentersPanel,
+                            ));
+                        });
+                });
+        });
+}
+
+fn section_header(parent: &mut ChildBuilder, title: &str) {
+    parent.spawn(
+        TextBundle::from_section(
+            title,
+            TextStyle { font_size: 20.0, color: LABEL_TEXT_COLOR, ..default() },
+        )
+        .with_style(Style { margin: UiRect::vertical(Val::Px(6.0)), ..default() }),
+    );
+}
+
+fn budget_line(parent: &mut ChildBuilder, label: &str, amount: f64, color: Color) {
+    parent.spawn(
+        TextBundle::from_section(
+            format!("{}: {:+.1} Cr/cycle", label, amount),
+            TextStyle { font_size: 16.0, color, ..default() },
+        )
+        .with_style(Style { margin: UiRect::bottom(Val::Px(2.0)), ..default() }),
+    );
+}
+
+fn action_button<B: Bundle>(parent: &mut ChildBuilder, label: String, enabled: bool, marker: B) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    margin: UiRect::bottom(Val::Px(4.0)),
+                    ..default()
+                },
+                background_color: if enabled { NORMAL_BUTTON.into() } else { DISABLED_BUTTON.into() },
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|btn| {
+            btn.spawn(TextBundle::from_section(
+                label,
+                TextStyle { font_size: 14.0, color: PRIMARY_TEXT_COLOR, ..default() },
+            ));
+        });
+}
+
+fn tax_rate_row(parent: &mut ChildBuilder, category: TaxCategory, label: String) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                margin: UiRect::bottom(Val::Px(4.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|row| {
+            for (increase, symbol) in [(false, "-"), (true, "+")] {
+                row.spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(28.0),
+                            justify_content: JustifyContent::Center,
+                            margin: UiRect::right(Val::Px(4.0)),
+                            ..default()
+                        },
+                        background_color: NORMAL_BUTTON.into(),
+                        ..default()
+                    },
+                    TaxRateButton(category, increase),
+                ))
+                .with_children(|btn| {
+                    btn.spawn(TextBundle::from_section(
+                        symbol,
+                        TextStyle { font_size: 16.0, color: PRIMARY_TEXT_COLOR, ..default() },
+                    ));
+                });
+            }
+            row.spawn(TextBundle::from_section(
+                label,
+                TextStyle { font_size: 16.0, color: PRIMARY_TEXT_COLOR, ..default() },
+            ));
+        });
+}
+
+pub(super) fn update_budget_panel_system(
+    current_app: Res<CurrentApp>,
+    game_state: Res<GameState>,
+    panel_query: Query<Entity, With<BudgetContentPanel>>,
+    mut commands: Commands,
+) {
+    if current_app.0 != AppType::Finance { return; }
+    if !game_state.is_changed() && !current_app.is_changed() { return; }
+
+    if let Ok(panel_entity) = panel_query.get_single() {
+        commands.entity(panel_entity).despawn_descendants();
+        let budget = project_budget(&game_state);
+        let rates = game_state.tax_rates;
+
+        commands.entity(panel_entity).with_children(|parent| {
+            section_header(parent, "TAX RATES");
+            tax_rate_row(parent, TaxCategory::Inhabitant, format!("Inhabitant Tax: {:.1} Cr/head", rates.inhabitant));
+            tax_rate_row(parent, TaxCategory::Specialist, format!("Specialist Tax: {:.1} Cr/head", rates.specialist));
+            tax_rate_row(parent, TaxCategory::Commercial, format!("Commercial Tax: {:.0}% of zone income", rates.commercial * 100.0));
+            let penalty = tax_happiness_penalty(&rates);
+            parent.spawn(TextBundle::from_section(
+                format!("Happiness impact: -{:.1}", penalty),
+                TextStyle { font_size: 14.0, color: if penalty > 0.0 { Color::YELLOW } else { LABEL_TEXT_COLOR }, ..default() },
+            ));
+
+            section_header(parent, "PROJECTED INCOME");
+            budget_line(parent, "Commercial Zones", budget.zone_income, Color::GOLD);
+            budget_line(parent, "Legacy Structure", budget.legacy_income, Color::GOLD);
+            budget_line(parent, "Inhabitant Tax", budget.inhabitant_tax, Color::GOLD);
+            budget_line(parent, "Specialist Tax", budget.specialist_tax, Color::GOLD);
+            budget_line(parent, "Commercial Tax", budget.commercial_tax, Color::GOLD);
+
+            section_header(parent, "UPKEEP");
+            budget_line(parent, "Fabricators", -budget.fabricator_upkeep, Color::TOMATO);
+            budget_line(parent, "Processing Plants", -budget.processing_plant_upkeep, Color::TOMATO);
+            budget_line(parent, "Zones", -budget.zone_upkeep, Color::TOMATO);
+            budget_line(parent, "Service Buildings", -budget.service_upkeep, Color::TOMATO);
+
+            section_header(parent, "DEBT SERVICE");
+            budget_line(parent, "Loan Payments", -budget.loan_payments, Color::TOMATO);
+            budget_line(parent, "Overdraft Interest", -budget.overdraft_interest, Color::TOMATO);
+
+            section_header(parent, "BALANCE");
+            budget_line(parent, "Total Income", budget.total_income(), Color::GOLD);
+            budget_line(parent, "Total Upkeep", -budget.total_upkeep(), Color::TOMATO);
+            budget_line(parent, "Debt Service", -budget.debt_service(), Color::TOMATO);
+            let net = budget.net();
+            budget_line(parent, "Net", net, if net >= 0.0 { Color::GREEN } else { Color::RED });
+
+            section_header(parent, "CREDIT & LOANS");
+            let (status_text, status_color) = match game_state.financial_status {
+                FinancialStatus::Solvent => ("Solvent".to_string(), Color::GREEN),
+                FinancialStatus::Overdrawn => ("Overdrawn".to_string(), Color::YELLOW),
+                FinancialStatus::Bankrupt => (
+                    format!(
+                        "Bankrupt - {:?} ({} cycles)",
+                        BankruptcyStage::from_cycles(game_state.bankruptcy_cycles),
+                        game_state.bankruptcy_cycles
+                    ),
+                    Color::RED,
+                ),
+            };
+            parent.spawn(TextBundle::from_section(
+                format!("Status: {}", status_text),
+                TextStyle { font_size: 16.0, color: status_color, ..default() },
+            ));
+            parent.spawn(TextBundle::from_section(
+                format!("Available credit: {:.0} / {:.0} Cr", (game_state.credits + CREDIT_LIMIT).clamp(0.0, CREDIT_LIMIT), CREDIT_LIMIT),
+                TextStyle { font_size: 14.0, color: LABEL_TEXT_COLOR, ..default() },
+            ).with_style(Style { margin: UiRect::bottom(Val::Px(6.0)), ..default() }));
+
+            for loan in &game_state.loans {
+                action_button(
+                    parent,
+                    format!(
+                        "Repay {} ({:.0} Cr left, {:.1} Cr/cycle)",
+                        loan.name, loan.balance, loan.installment
+                    ),
+                    game_state.credits >= loan.balance,
+                    RepayLoanButton(loan.id.clone()),
+                );
+            }
+
+            let can_borrow = game_state.financial_status != FinancialStatus::Bankrupt
+                && game_state.loans.len() < MAX_ACTIVE_LOANS;
+            for (index, offer) in get_loan_offers().iter().enumerate() {
+                action_button(
+                    parent,
+                    format!(
+                        "Take {} ({:.0} Cr, {:.1}%/cycle, {} cycles)",
+                        offer.name, offer.amount, offer.interest_rate * 100.0, offer.term_cycles
+                    ),
+                    can_borrow,
+                    TakeLoanButton(index),
+                );
+            }
+        });
+    }
+}
+
+pub(super) fn tax_rate_button_system(
+    mut interaction_query: Query<(&Interaction, &TaxRateButton), (Changed<Interaction>, With<Button>)>,
+    mut game_state: ResMut<GameState>,
+) {
+    for (interaction, button) in interaction_query.iter_mut() {
+        if *interaction == Interaction::Pressed {
+            game_state::adjust_tax_rate(&mut game_state, button.0, button.1);
+        }
+    }
+}
+
+pub(super) fn loan_button_system(
+    take_query: Query<(&Interaction, &TakeLoanButton), (Changed<Interaction>, With<Button>)>,
+    repay_query: Query<(&Interaction, &RepayLoanButton), (Changed<Interaction>, With<Button>)>,
+    mut game_state: ResMut<GameState>,
+) {
+    for (interaction, button) in take_query.iter() {
+        if *interaction == Interaction::Pressed {
+            game_state::take_loan(&mut game_state, button.0);
+        }
+    }
+    for (interaction, button) in repay_query.iter() {
+        if *interaction == Interaction::Pressed {
+            game_state::repay_loan(&mut game_state, &button.0);
+        }
+    }
+}
+
+pub(super) fn update_cost_centers_panel_system(
+    current_app: Res<CurrentApp>,
+    game_state: Res<GameState>,
+    panel_query: Query<Entity, With<CostCentersPanel>>,
+    mut commands: Commands,
+) {
+    if current_app.0 != AppType::Finance { return; }
+    if !game_state.is_changed() && !current_app.is_changed() { return; }
+
+    if let Ok(panel_entity) = panel_query.get_single() {
+        commands.entity(panel_entity).despawn_descendants();
+        let ledger = &game_state.ledger;
+
+        commands.entity(panel_entity).with_children(|parent| {
+            if let Some(last) = ledger.history.back() {
+                budget_line(parent, "Last Cycle Income", last.income, Color::GOLD);
+                budget_line(parent, "Last Cycle Expenses", -last.expenses, Color::TOMATO);
+            }
+
+            section_header(parent, "TOP COST CENTERS");
+            let centers = ledger.top_cost_centers(10);
+            if centers.is_empty() {
+                parent.spawn(TextBundle::from_section(
+                    "No expenses recorded yet.",
+                    TextStyle { font_size: 14.0, color: LABEL_TEXT_COLOR, ..default() },
+                ));
+            }
+            for (rank, (source, total)) in centers.iter().enumerate() {
+                parent.spawn(TextBundle::from_section(
+                    format!("{}. {}: {:.0} Cr", rank + 1, describe_ledger_source(&game_state, source), total),
+                    TextStyle { font_size: 14.0, color: PRIMARY_TEXT_COLOR, ..default() },
+                ));
+            }
+        });
+    }
+}
+
+pub(super) fn draw_ledger_graph_gizmos(
+    mut gizmos: Gizmos,
+    game_state: Res<GameState>,
+    graph_area_query: Query<(&Node, &GlobalTransform), With<LedgerGraphArea>>,
+) {
+    let history = &game_state.ledger.history;
+    if history.len() < 2 { return; }
+
+    let (graph_node, transform) = if let Ok(result) = graph_area_query.get_single() { result } else { return; };
+    let graph_area = graph_node.size();
+    if graph_area.x <= 0.0 || graph_area.y <= 0.0 { return; }
+
+    let bottom_left = transform.translation().truncate() - graph_area / 2.0;
+    let max_val = history.iter().fold(1.0f64, |max, summary| max.max(summary.income).max(summary.expenses)) as f32;
+
+    for (color, expenses) in [(Color::GOLD, false), (Color::TOMATO, true)] {
+        let points: Vec<Vec2> = history.iter().enumerate().map(|(i, summary)| {
+            let x = bottom_left.x + (i as f32 / (history.len() - 1) as f32) * graph_area.x;
+            let value = if expenses { summary.expenses } else { summary.income } as f32;
+            let y = bottom_left.y + (value / max_val) * graph_area.y;
+            Vec2::new(x, y.clamp(bottom_left.y, bottom_left.y + graph_area.y))
+        }).collect();
+        gizmos.linestrip_2d(points, color);
+    }
+}
@@ -0,0 +1,303 @@
+use bevy::prelude::*;
+use crate::game_state::{GameState, ServiceCoverage, ALL_SERVICE_TYPES};
+use crate::resources::spatial::{SpatialIndex, POWER_RELAY_RANGE, SITE_SPACING};
+use crate::systems::population::EMIGRATION_HAPPINESS_THRESHOLD;
+use super::*;
+
+#[derive(Component)]
+pub(super) struct MapPanel;
+#[derive(Component)]
+pub(super) struct MapArea;
+#[derive(Component)]
+pub(super) struct MapOverlayButton;
+#[derive(Component)]
+pub(super) struct MapOverlayButtonText;
+#[derive(Component)]
+pub(super) struct MapLegendText;
+
+pub(super) fn build(viewport: &mut ChildBuilder, _assets: &Res<AssetServer>) {
+    viewport
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            MapPanel,
+        ))
+        .with_children(|map| {
+            map.spawn(
+                TextBundle::from_section(
+                    "COLONY MAP",
+                    TextStyle {
+                        font_size: 28.0,
+                        color: BORDER_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                }),
+            );
+
+            map.spawn((
+                ButtonBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(5.0)),
+                        margin: UiRect::bottom(Val::Px(5.0)),
+                        ..default()
+                    },
+                    background_color: NORMAL_BUTTON.into(),
+                    ..default()
+                },
+                MapOverlayButton,
+            ))
+            .with_children(|btn| {
+                btn.spawn((
+                    TextBundle::from_section(
+                        "Overlay: All Services",
+                        TextStyle { font_size: 14.0, color: PRIMARY_TEXT_COLOR, ..default() },
+                    ),
+                    MapOverlayButtonText,
+                ));
+            });
+
+            map.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        flex_grow: 1.0,
+                        border: UiRect::all(Val::Px(1.0)),
+                        ..default()
+                    },
+                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.3).into(),
+                    border_color: BORDER_COLOR.into(),
+                    ..default()
+                },
+                MapArea,
+            ));
+
+            map.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle { font_size: 14.0, color: LABEL_TEXT_COLOR, ..default() },
+                )
+                .with_style(Style {
+                    margin: UiRect::top(Val::Px(5.0)),
+                    ..default()
+                }),
+                MapLegendText,
+            ));
+        });
+}
+
+fn overlay_label(overlay: MapOverlay) -> String {
+    match overlay {
+        MapOverlay::Coverage(Some(service_type)) => format!("Overlay: {:?}", service_type),
+        MapOverlay::Coverage(None) => "Overlay: All Services".to_string(),
+        MapOverlay::Happiness => "Overlay: Happiness".to_string(),
+    }
+}
+
+/// Red for miserable through yellow to green for content, blended smoothly for the heatmap.
+fn happiness_color(happiness: f32) -> Color {
+    let t = (happiness / 100.0).clamp(0.0, 1.0);
+    if t < 0.5 {
+        Color::rgb(1.0, t * 2.0, 0.0)
+    } else {
+        Color::rgb(2.0 - t * 2.0, 1.0, 0.0)
+    }
+}
+
+/// Red for no coverage through yellow to green for full coverage.
+fn coverage_color(ratio: f32) -> Color {
+    if ratio <= 0.0 {
+        Color::RED
+    } else if ratio < 0.5 {
+        Color::ORANGE
+    } else if ratio < 1.0 {
+        Color::YELLOW
+    } else {
+        Color::GREEN
+    }
+}
+
+pub(super) fn map_overlay_button_system(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<MapOverlayButton>)>,
+    mut overlay: ResMut<MapOverlay>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            // Cycles All -> each service type -> Happiness -> All.
+            *overlay = match *overlay {
+                MapOverlay::Coverage(None) => MapOverlay::Coverage(Some(ALL_SERVICE_TYPES[0])),
+                MapOverlay::Coverage(Some(current)) => ALL_SERVICE_TYPES
+                    .iter()
+                    .position(|s| *s == current)
+                    .and_then(|i| ALL_SERVICE_TYPES.get(i + 1))
+                    .map_or(MapOverlay::Happiness, |next| MapOverlay::Coverage(Some(*next))),
+                MapOverlay::Happiness => MapOverlay::Coverage(None),
+            };
+        }
+    }
+}
+
+pub(super) fn update_map_legend_system(
+    current_app: Res<CurrentApp>,
+    game_state: Res<GameState>,
+    coverage: Res<ServiceCoverage>,
+    spatial_index: Res<SpatialIndex>,
+    overlay: Res<MapOverlay>,
+    mut texts: ParamSet<(
+        Query<&mut Text, With<MapOverlayButtonText>>,
+        Query<&mut Text, With<MapLegendText>>,
+    )>,
+) {
+    if current_app.0 != AppType::Map { return; }
+    if !game_state.is_changed() && !overlay.is_changed() && !current_app.is_changed() { return; }
+
+    if let Ok(mut text) = texts.p0().get_single_mut() {
+        text.sections[0].value = overlay_label(*overlay);
+    }
+
+    if *overlay == MapOverlay::Happiness {
+        let unhappy: Vec<String> = game_state
+            .habitation_structures
+            .iter()
+            .filter_map(|h| {
+                let happiness = game_state.household_happiness.get(&h.id).copied()?;
+                (h.current_inhabitants > 0 && happiness < EMIGRATION_HAPPINESS_THRESHOLD)
+                    .then(|| format!("{} ({:.0}%)", h.id, happiness))
+            })
+            .collect();
+        if let Ok(mut text) = texts.p1().get_single_mut() {
+            text.sections[0].value = if unhappy.is_empty() {
+                "Heatmap: household happiness from red (miserable) to green (content).".to_string()
+            } else {
+                format!("Households at risk of emigrating: {}", unhappy.join(", "))
+            };
+            text.sections[0].style.color = if unhappy.is_empty() { LABEL_TEXT_COLOR } else { Color::ORANGE };
+        }
+        return;
+    }
+    let MapOverlay::Coverage(service) = *overlay else { return; };
+
+    let uncovered: Vec<&str> = game_state
+        .habitation_structures
+        .iter()
+        .filter(|h| h.current_inhabitants > 0 && coverage.structure_coverage(&h.id, service) <= 0.0)
+        .map(|h| h.id.as_str())
+        .collect();
+    let unpowered = game_state
+        .habitation_structures
+        .iter()
+        .filter_map(|h| h.position)
+        .filter(|p| !spatial_index.is_powered(*p))
+        .count();
+    if let Ok(mut text) = texts.p1().get_single_mut() {
+        let mut legend = if uncovered.is_empty() {
+            "Circles: habitation (colour = coverage). Squares: service buildings with their radius, orange for industry. Diamonds: power relays.".to_string()
+        } else {
+            format!("Structures with no coverage: {}", uncovered.join(", "))
+        };
+        if unpowered > 0 {
+            legend.push_str(&format!("\n{} habitation structure(s) outside power relay reach.", unpowered));
+        }
+        text.sections[0].value = legend;
+        text.sections[0].style.color = if uncovered.is_empty() && unpowered == 0 { LABEL_TEXT_COLOR } else { Color::ORANGE };
+    }
+}
+
+/// Sites of every placed extractor, fabricator and processing plant.
+fn industry_positions(game_state: &GameState) -> impl Iterator<Item = (f32, f32)> + '_ {
+    game_state
+        .extractors
+        .iter()
+        .filter_map(|e| e.position)
+        .chain(game_state.fabricators.iter().filter_map(|f| f.position))
+        .chain(game_state.processing_plants.iter().filter_map(|p| p.position))
+}
+
+pub(super) fn draw_map_gizmos(
+    mut gizmos: Gizmos,
+    current_app: Res<CurrentApp>,
+    game_state: Res<GameState>,
+    coverage: Res<ServiceCoverage>,
+    overlay: Res<MapOverlay>,
+    map_area_query: Query<(&Node, &GlobalTransform), With<MapArea>>,
+) {
+    if current_app.0 != AppType::Map { return; }
+
+    let (map_node, transform) = if let Ok(result) = map_area_query.get_single() { result } else { return; };
+    let map_area = map_node.size();
+    if map_area.x <= 0.0 || map_area.y <= 0.0 { return; }
+
+    let positions: Vec<(f32, f32)> = game_state
+        .habitation_structures
+        .iter()
+        .filter_map(|h| h.position)
+        .chain(game_state.service_buildings.iter().filter_map(|b| b.position))
+        .chain(game_state.power_relays.iter().filter_map(|r| r.position))
+        .chain(industry_positions(&game_state))
+        .collect();
+    if positions.is_empty() { return; }
+
+    // Fit every placed building into the map area with a one-site margin.
+    let (min_x, max_x, min_y, max_y) = positions.iter().fold(
+        (f32::MAX, f32::MIN, f32::MAX, f32::MIN),
+        |(min_x, max_x, min_y, max_y), p| (min_x.min(p.0), max_x.max(p.0), min_y.min(p.1), max_y.max(p.1)),
+    );
+    let world_size = Vec2::new(max_x - min_x, max_y - min_y) + Vec2::splat(SITE_SPACING * 2.0);
+    let scale = (map_area.x / world_size.x).min(map_area.y / world_size.y);
+    let center = transform.translation().truncate();
+    let world_center = Vec2::new(min_x + max_x, min_y + max_y) / 2.0;
+    let to_map = |p: (f32, f32)| center + (Vec2::new(p.0, p.1) - world_center) * scale;
+
+    if *overlay == MapOverlay::Happiness {
+        for structure in &game_state.habitation_structures {
+            let Some(position) = structure.position else { continue; };
+            let Some(happiness) = game_state.household_happiness.get(&structure.id) else { continue; };
+            let color = if structure.current_inhabitants == 0 { Color::DARK_GRAY } else { happiness_color(*happiness) };
+            // Concentric rings fading outwards give each household a soft heat spot.
+            for ring in 1..=6 {
+                let radius = SITE_SPACING * 0.5 * ring as f32 / 6.0 * scale;
+                gizmos.circle_2d(to_map(position), radius, color.with_a(1.0 - ring as f32 / 7.0));
+            }
+        }
+        return;
+    }
+    let MapOverlay::Coverage(service) = *overlay else { return; };
+
+    for building in &game_state.service_buildings {
+        let Some(position) = building.position else { continue; };
+        if service.is_some_and(|s| s != building.service_type) { continue; }
+        let color = if building.is_active { Color::CYAN } else { Color::GRAY };
+        gizmos.rect_2d(to_map(position), 0.0, Vec2::splat(SITE_SPACING * 0.4 * scale), color);
+        if let Some(tier) = building.available_tiers.get(building.current_tier_index) {
+            gizmos.circle_2d(to_map(position), tier.service_radius * scale, color.with_a(0.3));
+        }
+    }
+
+    for position in industry_positions(&game_state) {
+        gizmos.rect_2d(to_map(position), 0.0, Vec2::splat(SITE_SPACING * 0.4 * scale), Color::ORANGE);
+    }
+
+    for relay in &game_state.power_relays {
+        let Some(position) = relay.position else { continue; };
+        gizmos.rect_2d(to_map(position), std::f32::consts::FRAC_PI_4, Vec2::splat(SITE_SPACING * 0.3 * scale), Color::GOLD);
+        gizmos.circle_2d(to_map(position), POWER_RELAY_RANGE * scale, Color::GOLD.with_a(0.15));
+    }
+
+    for structure in &game_state.habitation_structures {
+        let Some(position) = structure.position else { continue; };
+        let ratio = coverage.structure_coverage(&structure.id, service);
+        let color = if structure.current_inhabitants == 0 { Color::DARK_GRAY } else { coverage_color(ratio) };
+        gizmos.circle_2d(to_map(position), SITE_SPACING * 0.3 * scale, color);
+    }
+}
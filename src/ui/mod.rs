@@ -17,10 +17,16 @@ mod dashboard;
 mod construction;
 mod colony_status;
 mod research;
+mod finance;
+mod workforce;
+mod map;
 use dashboard::{DashboardPanel, ManagedStructuresPanel, ZoneListButton, ZoneDetailsPanel, UpgradeZoneButton, RemoveZoneButton, AssignSpecialistToZoneButton, UnassignSpecialistFromZoneButton};
 use construction::ConstructionPanel;
 use colony_status::ColonyStatusPanel;
 use research::ResearchPanel;
+use finance::FinancePanel;
+use workforce::WorkforcePanel;
+use map::MapPanel;
 
 // --- Color & Style Constants ---
 const PANEL_BACKGROUND: Color = Color::rgba(0.02, 0.02, 0.05, 0.85);
@@ -76,10 +82,20 @@ pub enum AppType {
     Construction,
     ColonyStatus,
     Research,
+    Finance,
+    Workforce,
+    Map,
 }
 #[derive(Resource, Default)]
 pub struct CurrentApp(pub AppType);
 
+/// Drawer buttons use an image asset when one exists, otherwise a short text glyph.
+#[derive(Clone, Copy)]
+enum DrawerIcon {
+    Image(&'static str),
+    Glyph(&'static str),
+}
+
 #[derive(Resource, Default)]
 pub struct SelectedTech(pub Option<Tech>);
 
@@ -87,6 +103,22 @@ pub struct SelectedTech(pub Option<Tech>);
 pub struct SelectedZone(pub Option<String>);
 #[derive(Resource, Default)]
 pub struct SelectedServiceBuilding(pub Option<String>);
+#[derive(Resource, Default)]
+pub struct SelectedCitizen(pub usize);
+/// What the colony map colours habitation structures by.
+#[derive(Resource, Clone, Copy, PartialEq)]
+pub enum MapOverlay {
+    /// Coverage of one service, or the average of all services when `None`
+    Coverage(Option<ServiceType>),
+    /// Heatmap of each household's happiness
+    Happiness,
+}
+
+impl Default for MapOverlay {
+    fn default() -> Self {
+        MapOverlay::Coverage(None)
+    }
+}
 
 // --- Building Metadata ---
 
@@ -101,6 +133,8 @@ impl Plugin for UiPlugin {
             .init_resource::<SelectedTech>()
             .init_resource::<SelectedZone>()
             .init_resource::<SelectedServiceBuilding>()
+            .init_resource::<SelectedCitizen>()
+            .init_resource::<MapOverlay>()
             .add_systems(Startup, setup_ui)
                 .add_systems(Update, (
                     app_drawer_button_system,
@@ -124,6 +158,12 @@ impl Plugin for UiPlugin {
                     dashboard::unassign_specialist_from_service_building_button_interaction_system,
                     dashboard::admin_spire_button_interaction_system,
                     dashboard::legacy_structure_button_system,
+                    dashboard::update_upkeep_priority_panel_system,
+                    dashboard::upkeep_priority_button_system,
+                    dashboard::mothball_button_system,
+                    dashboard::update_building_status_panel_system,
+                    dashboard::notification_action_button_system,
+                    dashboard::upgrade_fabricator_button_interaction_system,
                 ))
                 .add_systems(Update, (
                     dashboard::draw_graph_gizmos,
@@ -143,6 +183,26 @@ impl Plugin for UiPlugin {
                     research::research_item_button_system,
                     research::update_research_details_panel_system,
                     research::initiate_research_button_system,
+                    research::research_queue_button_system,
+                    research::institute_field_button_system,
+                ))
+                .add_systems(Update, (
+                    finance::update_budget_panel_system,
+                    finance::tax_rate_button_system,
+                    finance::loan_button_system,
+                    finance::update_cost_centers_panel_system,
+                    finance::draw_ledger_graph_gizmos,
+                    workforce::update_workforce_panel_system,
+                    workforce::workforce_button_system,
+                    colony_status::citizen_inspector_button_system,
+                    colony_status::update_citizen_inspector_system,
+                    colony_status::draw_cohort_graph_gizmos,
+                    colony_status::update_happiness_breakdown_system,
+                ))
+                .add_systems(Update, (
+                    map::map_overlay_button_system,
+                    map::update_map_legend_system,
+                    map::draw_map_gizmos,
                 ));
     }
 }
@@ -209,12 +269,15 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                 ..default()
             }).with_children(|drawer| {
                 let apps = [
-                    (AppType::Dashboard, "icon_dashboard.png"),
-                    (AppType::Construction, "icon_construction.png"),
-                    (AppType::ColonyStatus, "icon_colony_status.png"),
-                    (AppType::Research, "icon_research.png"),
+                    (AppType::Dashboard, DrawerIcon::Image("icon_dashboard.png")),
+                    (AppType::Construction, DrawerIcon::Image("icon_construction.png")),
+                    (AppType::ColonyStatus, DrawerIcon::Image("icon_colony_status.png")),
+                    (AppType::Research, DrawerIcon::Image("icon_research.png")),
+                    (AppType::Finance, DrawerIcon::Glyph("Cr")),
+                    (AppType::Workforce, DrawerIcon::Glyph("Wk")),
+                    (AppType::Map, DrawerIcon::Glyph("Mp")),
                 ];
-                for (app_type, icon_path) in apps {
+                for (app_type, icon) in apps {
                     drawer.spawn((
                         ButtonBundle {
                             style: Style {
@@ -232,15 +295,22 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                         },
                         AppDrawerButton(app_type),
                     )).with_children(|button| {
-                        button.spawn(ImageBundle {
-                            style: Style {
-                                width: Val::Percent(70.0),
-                                height: Val::Percent(70.0),
-                                ..default()
-                            },
-                            image: UiImage::new(asset_server.load(icon_path)),
-                            ..default()
-                        });
+                        match icon {
+                            DrawerIcon::Image(icon_path) => {
+                                button.spawn(ImageBundle {
+                                    style: Style {
+                                        width: Val::Percent(70.0),
+                                        height: Val::Percent(70.0),
+                                        ..default()
+                                    },
+                                    image: UiImage::new(asset_server.load(icon_path)),
+                                    ..default()
+                                });
+                            }
+                            DrawerIcon::Glyph(glyph) => {
+                                button.spawn(TextBundle::from_section(glyph, TextStyle { font_size: 20.0, color: Color::CYAN, ..default() }));
+                            }
+                        }
                     });
                 }
             });
@@ -259,6 +329,9 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                 construction::build(viewport, &asset_server);
                 colony_status::build(viewport, &asset_server);
                 research::build(viewport, &asset_server);
+                finance::build(viewport, &asset_server);
+                workforce::build(viewport, &asset_server);
+                map::build(viewport, &asset_server);
             });
         });
     });
@@ -294,6 +367,9 @@ fn manage_app_panels_visibility(
         Query<&mut Style, With<ConstructionPanel>>,
         Query<&mut Style, With<ColonyStatusPanel>>,
         Query<&mut Style, With<ResearchPanel>>,
+        Query<&mut Style, With<FinancePanel>>,
+        Query<&mut Style, With<WorkforcePanel>>,
+        Query<&mut Style, With<MapPanel>>,
     )>,
 ) {
     if !current_app.is_changed() { return; }
@@ -302,12 +378,18 @@ fn manage_app_panels_visibility(
     for mut style in panel_queries.p1().iter_mut() { style.display = Display::None; }
     for mut style in panel_queries.p2().iter_mut() { style.display = Display::None; }
     for mut style in panel_queries.p3().iter_mut() { style.display = Display::None; }
+    for mut style in panel_queries.p4().iter_mut() { style.display = Display::None; }
+    for mut style in panel_queries.p5().iter_mut() { style.display = Display::None; }
+    for mut style in panel_queries.p6().iter_mut() { style.display = Display::None; }
 
     match current_app.0 {
         AppType::Dashboard => panel_queries.p0().single_mut().display = Display::Flex,
         AppType::Construction => panel_queries.p1().single_mut().display = Display::Flex,
         AppType::ColonyStatus => panel_queries.p2().single_mut().display = Display::Flex,
         AppType::Research => panel_queries.p3().single_mut().display = Display::Flex,
+        AppType::Finance => panel_queries.p4().single_mut().display = Display::Flex,
+        AppType::Workforce => panel_queries.p5().single_mut().display = Display::Flex,
+        AppType::Map => panel_queries.p6().single_mut().display = Display::Flex,
     }
 }
 
@@ -328,7 +410,7 @@ fn update_status_ticker_system(
 
     // Power
     let net_power = game_state.total_generated_power - game_state.total_consumed_power;
-    let stored_power = *game_state.current_resources.get(&ResourceType::Power).unwrap_or(&0.0);
+    let stored_power = game_state.current_resources.get(ResourceType::Power);
     let mut p1 = queries.p1();
     let mut power_text = p1.single_mut();
     power_text.sections[0].value = format!("⚡ {:+.0} | 🔋 {:.0}", net_power, stored_power);
@@ -372,9 +454,17 @@ fn update_status_ticker_system(
 
     // Core Resources
     for (mut text, marker) in queries.p5().iter_mut() {
-        let amount = game_state.current_resources.get(&marker.0).unwrap_or(&0.0);
+        let amount = game_state.current_resources.get(marker.0);
         let capacity = BASE_STORAGE_CAPACITY + game_state.storage_silos.len() as f32 * STORAGE_SILO_CAPACITY;
-        text.sections[0].value = format!("{:?}: {:.0}/{:.0}", marker.0, amount, capacity);
+        let flow = game_state.resource_accounts.rate(marker.0);
+        let depletion = match game_state.resource_accounts.ticks_to_depletion(marker.0, amount) {
+            Some(ticks) => format!(", empty in {:.0} ticks", ticks),
+            None => String::new(),
+        };
+        text.sections[0].value = format!(
+            "{:?}: {:.0}/{:.0} (+{:.1} / -{:.1} per tick{})",
+            marker.0, amount, capacity, flow.produced, flow.consumed, depletion
+        );
     }
 
     // Happiness
@@ -1,5 +1,9 @@
 use bevy::prelude::*;
-use crate::game_state::{self, GameState, Tech};
+use crate::game_state::{
+    self, is_research_active, research_field_progress, tech_effects, tech_eureka, tech_lock_reason, tech_node, GameState, Tech,
+    ALL_RESEARCH_FIELDS, ALL_TECHS,
+};
+use crate::systems::research::{research_etas, research_points_per_tick};
 use super::*;
 
 #[derive(Component)]
@@ -12,6 +16,17 @@ pub(super) struct ResearchItemButton(pub Tech);
 pub(super) struct ResearchDetailsPanel;
 #[derive(Component)]
 pub(super) struct InitiateResearchButton;
+#[derive(Component)]
+pub(super) struct ResearchQueueButton(pub Tech, pub QueueAction);
+#[derive(Component)]
+pub(super) struct InstituteFieldButton(pub String);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) enum QueueAction {
+    Earlier,
+    Later,
+    Remove,
+}
 
 pub(super) fn build(viewport: &mut ChildBuilder, _assets: &Res<AssetServer>) {
     viewport
@@ -89,6 +104,20 @@ pub(super) fn build(viewport: &mut ChildBuilder, _assets: &Res<AssetServer>) {
                 });
         });
 }
+fn eta_label(eta: Option<u32>) -> String {
+    eta.map_or("ETA: --".to_string(), |ticks| format!("ETA: {} ticks", ticks))
+}
+
+/// "Engineering 40/150, Physics 0/100" for each field `tech` draws on.
+fn field_progress_label(game_state: &GameState, tech: Tech) -> String {
+    tech_node(tech)
+        .research_costs
+        .iter()
+        .map(|(field, cost)| format!("{} {:.0}/{}", field.label(), research_field_progress(game_state, tech, *field), cost))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 pub(super) fn update_research_panel_system(
     current_app: Res<CurrentApp>,
     game_state: Res<GameState>,
@@ -100,13 +129,76 @@ pub(super) fn update_research_panel_system(
 
     if let Ok(panel_entity) = list_panel_query.get_single_mut() {
         commands.entity(panel_entity).despawn_descendants();
-        let all_techs = [Tech::BasicConstructionProtocols, Tech::EfficientExtraction];
+        let points = research_points_per_tick(&game_state);
+        let etas = research_etas(&game_state, &points);
         commands.entity(panel_entity).with_children(|parent| {
-            for tech in all_techs {
-                if !game_state.unlocked_techs.contains(&tech) {
-                     parent.spawn((ButtonBundle{ style: Style { width: Val::Percent(100.0), padding: UiRect::all(Val::Px(8.0)), margin: UiRect::bottom(Val::Px(4.0)), ..default() }, background_color: NORMAL_BUTTON.into(), ..default()}, ResearchItemButton(tech)))
-                     .with_children(|b| {b.spawn(TextBundle::from_section(tech.to_string(), TextStyle { color: PRIMARY_TEXT_COLOR, ..default() }));});
+            parent.spawn(TextBundle::from_section("OUTPUT (points/tick)", TextStyle { font_size: 16.0, color: LABEL_TEXT_COLOR, ..default() }));
+            for field in ALL_RESEARCH_FIELDS {
+                let rate = points.get(&field).copied().unwrap_or(0.0);
+                parent.spawn(TextBundle::from_section(
+                    format!("{}: {:.1}", field.label(), rate),
+                    TextStyle { font_size: 14.0, color: if rate > 0.0 { PRIMARY_TEXT_COLOR } else { Color::ORANGE }, ..default() },
+                ));
+            }
+
+            parent.spawn(TextBundle::from_section("INSTITUTES", TextStyle { font_size: 16.0, color: LABEL_TEXT_COLOR, ..default() })
+                .with_style(Style { margin: UiRect::top(Val::Px(6.0)), ..default() }));
+            if game_state.research_institutes.is_empty() {
+                parent.spawn(TextBundle::from_section("No research institutes", TextStyle { font_size: 14.0, color: LABEL_TEXT_COLOR, ..default() }));
+            }
+            for institute in &game_state.research_institutes {
+                parent.spawn(NodeBundle { style: Style { flex_direction: FlexDirection::Row, align_items: AlignItems::Center, margin: UiRect::bottom(Val::Px(2.0)), ..default() }, ..default() })
+                .with_children(|row| {
+                    row.spawn(TextBundle::from_section(
+                        format!("Institute {}", institute.id),
+                        TextStyle { font_size: 14.0, color: PRIMARY_TEXT_COLOR, ..default() },
+                    ).with_style(Style { flex_grow: 1.0, ..default() }));
+                    row.spawn((ButtonBundle { style: Style { padding: UiRect::horizontal(Val::Px(6.0)), ..default() }, background_color: NORMAL_BUTTON.into(), ..default() }, InstituteFieldButton(institute.id.clone())))
+                    .with_children(|b| { b.spawn(TextBundle::from_section(institute.field.label(), TextStyle { font_size: 14.0, color: PRIMARY_TEXT_COLOR, ..default() })); });
+                });
+            }
+
+            parent.spawn(TextBundle::from_section("QUEUE", TextStyle { font_size: 16.0, color: LABEL_TEXT_COLOR, ..default() })
+                .with_style(Style { margin: UiRect::top(Val::Px(6.0)), ..default() }));
+            if game_state.research_queue.is_empty() {
+                parent.spawn(TextBundle::from_section("Nothing queued", TextStyle { font_size: 14.0, color: LABEL_TEXT_COLOR, ..default() }));
+            }
+            for (position, tech) in game_state.research_queue.iter().enumerate() {
+                let active = is_research_active(&game_state, *tech);
+                parent.spawn(NodeBundle { style: Style { flex_direction: FlexDirection::Row, align_items: AlignItems::Center, margin: UiRect::bottom(Val::Px(2.0)), ..default() }, ..default() })
+                .with_children(|row| {
+                    row.spawn(NodeBundle { style: Style { flex_direction: FlexDirection::Column, flex_grow: 1.0, ..default() }, ..default() })
+                    .with_children(|text| {
+                        text.spawn(TextBundle::from_section(
+                            format!("{}. {} {}", position + 1, tech, eta_label(etas.get(tech).copied().flatten())),
+                            TextStyle { font_size: 14.0, color: if active { PRIMARY_TEXT_COLOR } else { LABEL_TEXT_COLOR }, ..default() },
+                        ));
+                        let status = if active { field_progress_label(&game_state, *tech) } else { "Waiting on prerequisites".to_string() };
+                        text.spawn(TextBundle::from_section(status, TextStyle { font_size: 12.0, color: LABEL_TEXT_COLOR, ..default() }));
+                    });
+                    for (label, action) in [("^", QueueAction::Earlier), ("v", QueueAction::Later), ("x", QueueAction::Remove)] {
+                        row.spawn((ButtonBundle { style: Style { padding: UiRect::horizontal(Val::Px(6.0)), margin: UiRect::left(Val::Px(2.0)), ..default() }, background_color: NORMAL_BUTTON.into(), ..default() }, ResearchQueueButton(*tech, action)))
+                        .with_children(|b| { b.spawn(TextBundle::from_section(label, TextStyle { font_size: 14.0, color: PRIMARY_TEXT_COLOR, ..default() })); });
+                    }
+                });
+            }
+
+            parent.spawn(TextBundle::from_section("TECHNOLOGIES", TextStyle { font_size: 16.0, color: LABEL_TEXT_COLOR, ..default() })
+                .with_style(Style { margin: UiRect::vertical(Val::Px(6.0)), ..default() }));
+            for tech in ALL_TECHS {
+                if game_state.unlocked_techs.contains(&tech) {
+                    continue;
                 }
+                // Locked techs stay listed, with the reason, so the player can see what opens them up.
+                let reason = tech_lock_reason(&game_state, tech);
+                let available = reason.is_none();
+                parent.spawn((ButtonBundle{ style: Style { width: Val::Percent(100.0), padding: UiRect::all(Val::Px(8.0)), margin: UiRect::bottom(Val::Px(4.0)), flex_direction: FlexDirection::Column, ..default() }, background_color: if available { NORMAL_BUTTON.into() } else { DISABLED_BUTTON.into() }, ..default()}, ResearchItemButton(tech)))
+                .with_children(|b| {
+                    b.spawn(TextBundle::from_section(tech.to_string(), TextStyle { color: if available { PRIMARY_TEXT_COLOR } else { LABEL_TEXT_COLOR }, ..default() }));
+                    if let Some(reason) = reason {
+                        b.spawn(TextBundle::from_section(reason, TextStyle { font_size: 12.0, color: LABEL_TEXT_COLOR, ..default() }));
+                    }
+                });
             }
         });
     }
@@ -139,27 +231,63 @@ pub(super) fn update_research_details_panel_system(
         commands.entity(panel).despawn_descendants();
         if let Some(tech) = selected_tech.0 {
             let cost = *game_state.tech_costs.get(&tech).unwrap_or(&0);
-            let (progress, active) = game_state.research_progress.as_ref().map(|(t,p)| (*p as u32, Some(*t))).unwrap_or((0,None));
+            let points = research_points_per_tick(&game_state);
             commands.entity(panel).with_children(|parent| {
                 parent.spawn(TextBundle::from_section(
                     tech.to_string(),
                     TextStyle { font_size: 22.0, color: PRIMARY_TEXT_COLOR, ..default() },
                 ));
+                let node = tech_node(tech);
+                let field_costs: Vec<String> = node.research_costs.iter().map(|(field, points)| format!("{} {}", points, field.label())).collect();
                 parent.spawn(TextBundle::from_section(
-                    format!("Cost: {} Credits", cost),
+                    format!("Cost: {} Credits | {} points", cost, field_costs.join(", ")),
                     TextStyle { color: LABEL_TEXT_COLOR, ..default() },
                 ));
+                let prerequisites: Vec<String> = node.prerequisites.iter().map(|t| t.to_string()).collect();
+                parent.spawn(TextBundle::from_section(
+                    format!(
+                        "Prerequisites: {} | Development Phase {:?}",
+                        if prerequisites.is_empty() { "None".to_string() } else { prerequisites.join(", ") },
+                        node.required_phase
+                    ),
+                    TextStyle { font_size: 14.0, color: LABEL_TEXT_COLOR, ..default() },
+                ));
+                let effects: Vec<String> = tech_effects(tech).iter().map(|e| format!("- {}", e.describe())).collect();
+                parent.spawn(TextBundle::from_section(
+                    format!("Effects:\n{}", effects.join("\n")),
+                    TextStyle { font_size: 16.0, color: PRIMARY_TEXT_COLOR, ..default() },
+                ).with_style(Style { margin: UiRect::vertical(Val::Px(5.0)), ..default() }));
+                if let Some(eureka) = tech_eureka(tech) {
+                    let claimed = game_state.eureka_claimed.contains(&tech);
+                    parent.spawn(TextBundle::from_section(
+                        format!(
+                            "Eureka: {} (+{:.0}% progress){}",
+                            eureka.description,
+                            eureka.share * 100.0,
+                            if claimed { " - achieved" } else { "" }
+                        ),
+                        TextStyle { font_size: 14.0, color: if claimed { Color::GREEN } else { LABEL_TEXT_COLOR }, ..default() },
+                    ).with_style(Style { margin: UiRect::bottom(Val::Px(5.0)), ..default() }));
+                }
 
-                if let Some(active_tech) = active {
-                    if active_tech == tech {
-                        parent.spawn(TextBundle::from_section(
-                            format!("Progress: {}/{}", progress, cost),
-                            TextStyle { color: LABEL_TEXT_COLOR, ..default() },
-                        ));
-                    } else {
+                let queue_position = game_state.research_queue.iter().position(|t| *t == tech);
+                if let Some(position) = queue_position {
+                    let eta = research_etas(&game_state, &points).get(&tech).copied().flatten();
+                    parent.spawn(TextBundle::from_section(
+                        format!("Queued: position {} of {} | {}", position + 1, game_state.research_queue.len(), eta_label(eta)),
+                        TextStyle { color: LABEL_TEXT_COLOR, ..default() },
+                    ));
+                    for (field, field_cost) in node.research_costs {
+                        let rate = points.get(field).copied().unwrap_or(0.0);
                         parent.spawn(TextBundle::from_section(
-                            "Another project is in progress",
-                            TextStyle { color: LABEL_TEXT_COLOR, ..default() },
+                            format!(
+                                "{}: {:.0}/{} | {:.1} points/tick",
+                                field.label(),
+                                research_field_progress(&game_state, tech, *field),
+                                field_cost,
+                                rate
+                            ),
+                            TextStyle { font_size: 14.0, color: if rate > 0.0 { LABEL_TEXT_COLOR } else { Color::ORANGE }, ..default() },
                         ));
                     }
                 } else if game_state.unlocked_techs.contains(&tech) {
@@ -167,9 +295,14 @@ pub(super) fn update_research_details_panel_system(
                         "Completed",
                         TextStyle { color: Color::GREEN, ..default() },
                     ));
+                } else if let Some(reason) = tech_lock_reason(&game_state, tech) {
+                    parent.spawn(TextBundle::from_section(
+                        format!("Locked: {}", reason),
+                        TextStyle { color: Color::ORANGE, ..default() },
+                    ));
                 }
 
-            let can_start = game_state.research_progress.is_none() && !game_state.unlocked_techs.contains(&tech) && game_state.credits >= cost as f64;
+            let can_start = tech_lock_reason(&game_state, tech).is_none() && game_state.credits >= cost as f64;
 
             parent
                 .spawn((
@@ -188,7 +321,7 @@ pub(super) fn update_research_details_panel_system(
                 ))
                 .with_children(|button| {
                     button.spawn(TextBundle::from_section(
-                        "RESEARCH",
+                        "QUEUE RESEARCH",
                         TextStyle { color: PRIMARY_TEXT_COLOR, ..default() },
                     ));
                 });
@@ -204,13 +337,33 @@ pub(super) fn initiate_research_button_system(
 ){
     if let Ok(Interaction::Pressed) = interaction_q.get_single() {
         if let Some(tech) = selected_tech.0 {
-            let cost = *game_state.tech_costs.get(&tech).unwrap_or(&0) as f64;
-            if game_state.credits >= cost {
-                if game_state.research_progress.is_none() {
-                    game_state.credits -= cost;
-                    game_state.research_progress = Some((tech, 0.0));
-                }
+            game_state::queue_research(&mut game_state, tech);
+        }
+    }
+}
+
+pub(super) fn research_queue_button_system(
+    interaction_query: Query<(&Interaction, &ResearchQueueButton), (Changed<Interaction>, With<Button>)>,
+    mut game_state: ResMut<GameState>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            match button.1 {
+                QueueAction::Earlier => game_state::move_research_in_queue(&mut game_state, button.0, true),
+                QueueAction::Later => game_state::move_research_in_queue(&mut game_state, button.0, false),
+                QueueAction::Remove => game_state::dequeue_research(&mut game_state, button.0),
             }
         }
     }
 }
+
+pub(super) fn institute_field_button_system(
+    interaction_query: Query<(&Interaction, &InstituteFieldButton), (Changed<Interaction>, With<Button>)>,
+    mut game_state: ResMut<GameState>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            game_state::cycle_institute_field(&mut game_state, &button.0);
+        }
+    }
+}
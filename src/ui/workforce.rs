@@ -0,0 +1,352 @@
+use bevy::prelude::*;
+use crate::game_state::{
+    self, effective_workforce_priority, staffing_fraction, GameState, SpecialistCategory, WorkPriority,
+    WorkforceCategory, RESEARCH_INSTITUTE_SPECIALISTS,
+};
+use super::*;
+
+#[derive(Component)]
+pub(super) struct WorkforcePanel;
+#[derive(Component)]
+pub(super) struct WorkforceContentPanel;
+#[derive(Component)]
+pub(super) struct WorkforcePriorityButton(pub WorkforceCategory, pub bool);
+#[derive(Component)]
+pub(super) struct AutoBalanceButton;
+#[derive(Component)]
+pub(super) struct WorkPriorityButton(pub String);
+#[derive(Component)]
+pub(super) struct SpecialistPriorityButton(pub SpecialistCategory, pub bool);
+#[derive(Component)]
+pub(super) struct SpecialistAutoAssignButton;
+#[derive(Component)]
+pub(super) struct ManualLockButton(pub String);
+
+pub(super) fn build(viewport: &mut ChildBuilder, _assets: &Res<AssetServer>) {
+    viewport
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            WorkforcePanel,
+        ))
+        .with_children(|workforce| {
+            workforce.spawn(
+                TextBundle::from_section(
+                    "WORKFORCE ALLOCATION",
+                    TextStyle {
+                        font_size: 28.0,
+                        color: BORDER_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                }),
+            );
+
+            workforce.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        flex_grow: 1.0,
+                        border: UiRect::all(Val::Px(1.0)),
+                        padding: UiRect::all(Val::Px(10.0)),
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    },
+                    border_color: BORDER_COLOR.into(),
+                    ..default()
+                },
+                WorkforceContentPanel,
+            ));
+        });
+}
+
+fn section_header(parent: &mut ChildBuilder, title: &str) {
+    parent.spawn(
+        TextBundle::from_section(
+            title,
+            TextStyle { font_size: 20.0, color: LABEL_TEXT_COLOR, ..default() },
+        )
+        .with_style(Style { margin: UiRect::vertical(Val::Px(6.0)), ..default() }),
+    );
+}
+
+fn small_button<B: Bundle>(parent: &mut ChildBuilder, label: &str, marker: B) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    padding: UiRect::horizontal(Val::Px(6.0)),
+                    margin: UiRect::right(Val::Px(4.0)),
+                    ..default()
+                },
+                background_color: NORMAL_BUTTON.into(),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|btn| {
+            btn.spawn(TextBundle::from_section(
+                label,
+                TextStyle { font_size: 14.0, color: PRIMARY_TEXT_COLOR, ..default() },
+            ));
+        });
+}
+
+fn building_row(parent: &mut ChildBuilder, name: &str, id: &str, assigned: u32, full_crew: u32, priority: WorkPriority) {
+    let fraction = staffing_fraction(assigned, full_crew);
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                margin: UiRect::bottom(Val::Px(3.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|row| {
+            small_button(row, &format!("{:?}", priority), WorkPriorityButton(id.to_string()));
+            row.spawn(TextBundle::from_section(
+                format!("{} {}: {}/{} workers ({:.0}% output)", name, id, assigned, full_crew, fraction * 100.0),
+                TextStyle {
+                    font_size: 14.0,
+                    color: if fraction > 0.0 { PRIMARY_TEXT_COLOR } else { Color::ORANGE },
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn specialist_row(parent: &mut ChildBuilder, name: &str, id: &str, assigned: u32, required: u32, locked: bool) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                margin: UiRect::bottom(Val::Px(3.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|row| {
+            small_button(row, if locked { "Locked" } else { "Auto" }, ManualLockButton(id.to_string()));
+            row.spawn(TextBundle::from_section(
+                format!("{} {}: {}/{} specialists", name, id, assigned, required),
+                TextStyle {
+                    font_size: 14.0,
+                    color: if assigned < required { Color::ORANGE } else { PRIMARY_TEXT_COLOR },
+                    ..default()
+                },
+            ));
+        });
+}
+
+pub(super) fn update_workforce_panel_system(
+    current_app: Res<CurrentApp>,
+    game_state: Res<GameState>,
+    panel_query: Query<Entity, With<WorkforceContentPanel>>,
+    mut commands: Commands,
+) {
+    if current_app.0 != AppType::Workforce { return; }
+    if !game_state.is_changed() && !current_app.is_changed() { return; }
+
+    if let Ok(panel_entity) = panel_query.get_single() {
+        commands.entity(panel_entity).despawn_descendants();
+        let effective_order = effective_workforce_priority(&game_state);
+
+        commands.entity(panel_entity).with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                format!(
+                    "Workers assigned: {} / {}",
+                    game_state.assigned_workforce, game_state.total_inhabitants
+                ),
+                TextStyle { font_size: 16.0, color: PRIMARY_TEXT_COLOR, ..default() },
+            ));
+
+            section_header(parent, "CATEGORY PRIORITY");
+            for (rank, category) in game_state.workforce_priority.iter().enumerate() {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            margin: UiRect::bottom(Val::Px(3.0)),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        small_button(row, "Up", WorkforcePriorityButton(*category, true));
+                        small_button(row, "Dn", WorkforcePriorityButton(*category, false));
+                        row.spawn(TextBundle::from_section(
+                            format!("{}. {} ({} workers each)", rank + 1, category.label(), category.full_crew()),
+                            TextStyle { font_size: 14.0, color: LABEL_TEXT_COLOR, ..default() },
+                        ));
+                    });
+            }
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Percent(100.0),
+                            padding: UiRect::all(Val::Px(5.0)),
+                            margin: UiRect::vertical(Val::Px(4.0)),
+                            ..default()
+                        },
+                        background_color: if game_state.workforce_auto_balance { ACTIVE_BUTTON.into() } else { NORMAL_BUTTON.into() },
+                        ..default()
+                    },
+                    AutoBalanceButton,
+                ))
+                .with_children(|btn| {
+                    btn.spawn(TextBundle::from_section(
+                        if game_state.workforce_auto_balance { "Auto-balance: ON" } else { "Auto-balance: OFF" },
+                        TextStyle { font_size: 14.0, color: PRIMARY_TEXT_COLOR, ..default() },
+                    ));
+                });
+            if game_state.workforce_auto_balance {
+                let order: Vec<&str> = effective_order.iter().map(|c| c.label()).collect();
+                parent.spawn(TextBundle::from_section(
+                    format!("Current order: {}", order.join(" > ")),
+                    TextStyle { font_size: 14.0, color: Color::CYAN, ..default() },
+                ));
+            }
+
+            section_header(parent, "BUILDINGS");
+            for extractor in &game_state.extractors {
+                building_row(parent, "Extractor", &extractor.id, extractor.assigned_workers, WorkforceCategory::Extractors.full_crew(), extractor.work_priority);
+            }
+            for dome in &game_state.bio_domes {
+                building_row(parent, "Bio-Dome", &dome.id, dome.assigned_workers, WorkforceCategory::BioDomes.full_crew(), dome.work_priority);
+            }
+            for institute in &game_state.research_institutes {
+                building_row(parent, "Research Institute", &institute.id, institute.assigned_workers, WorkforceCategory::ResearchInstitutes.full_crew(), institute.work_priority);
+            }
+
+            section_header(parent, "SPECIALISTS");
+            parent.spawn(TextBundle::from_section(
+                format!(
+                    "Specialists assigned: {} / {} slots",
+                    game_state.assigned_specialists_total, game_state.total_specialist_slots
+                ),
+                TextStyle { font_size: 16.0, color: PRIMARY_TEXT_COLOR, ..default() },
+            ));
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Percent(100.0),
+                            padding: UiRect::all(Val::Px(5.0)),
+                            margin: UiRect::vertical(Val::Px(4.0)),
+                            ..default()
+                        },
+                        background_color: if game_state.specialist_auto_assign { ACTIVE_BUTTON.into() } else { NORMAL_BUTTON.into() },
+                        ..default()
+                    },
+                    SpecialistAutoAssignButton,
+                ))
+                .with_children(|btn| {
+                    btn.spawn(TextBundle::from_section(
+                        if game_state.specialist_auto_assign { "Auto-assign: ON" } else { "Auto-assign: OFF" },
+                        TextStyle { font_size: 14.0, color: PRIMARY_TEXT_COLOR, ..default() },
+                    ));
+                });
+            for (rank, category) in game_state.specialist_priority.iter().enumerate() {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            margin: UiRect::bottom(Val::Px(3.0)),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        small_button(row, "Up", SpecialistPriorityButton(*category, true));
+                        small_button(row, "Dn", SpecialistPriorityButton(*category, false));
+                        row.spawn(TextBundle::from_section(
+                            format!("{}. {}", rank + 1, category.label()),
+                            TextStyle { font_size: 14.0, color: LABEL_TEXT_COLOR, ..default() },
+                        ));
+                    });
+            }
+            for building in &game_state.service_buildings {
+                let required = building.available_tiers.get(building.current_tier_index).map_or(0, |t| t.specialist_requirement);
+                specialist_row(parent, &format!("{:?}", building.service_type), &building.id, building.assigned_specialists, required, building.manual_lock);
+            }
+            for fab in &game_state.fabricators {
+                let required = fab.available_tiers.get(fab.tier_index).map_or(0, |t| t.specialist_requirement);
+                specialist_row(parent, "Fabricator", &fab.id, fab.assigned_specialists, required, fab.manual_lock);
+            }
+            for plant in &game_state.processing_plants {
+                let required = plant.available_tiers.get(plant.tier_index).map_or(0, |t| t.specialist_requirement);
+                specialist_row(parent, "Processing Plant", &plant.id, plant.assigned_specialists, required, plant.manual_lock);
+            }
+            for zone in &game_state.zones {
+                let required = zone.available_tiers.get(zone.current_tier_index).map_or(0, |t| t.specialist_jobs_provided);
+                specialist_row(parent, &format!("{:?} Zone", zone.zone_type), &zone.id, zone.assigned_specialists, required, zone.manual_lock);
+            }
+            for structure in &game_state.habitation_structures {
+                let required = structure.available_tiers.get(structure.tier_index).map_or(0, |t| t.specialist_slots);
+                specialist_row(parent, "Habitation", &structure.id, structure.assigned_specialists, required, structure.manual_lock);
+            }
+            for institute in &game_state.research_institutes {
+                specialist_row(parent, "Research Institute", &institute.id, institute.assigned_specialists, RESEARCH_INSTITUTE_SPECIALISTS, institute.manual_lock);
+            }
+        });
+    }
+}
+
+pub(super) fn workforce_button_system(
+    priority_query: Query<(&Interaction, &WorkforcePriorityButton), (Changed<Interaction>, With<Button>)>,
+    auto_balance_query: Query<&Interaction, (Changed<Interaction>, With<AutoBalanceButton>)>,
+    building_query: Query<(&Interaction, &WorkPriorityButton), (Changed<Interaction>, With<Button>)>,
+    specialist_priority_query: Query<(&Interaction, &SpecialistPriorityButton), (Changed<Interaction>, With<Button>)>,
+    auto_assign_query: Query<&Interaction, (Changed<Interaction>, With<SpecialistAutoAssignButton>)>,
+    lock_query: Query<(&Interaction, &ManualLockButton), (Changed<Interaction>, With<Button>)>,
+    mut game_state: ResMut<GameState>,
+) {
+    for (interaction, button) in priority_query.iter() {
+        if *interaction == Interaction::Pressed {
+            game_state::move_workforce_priority(&mut game_state, button.0, button.1);
+        }
+    }
+    for interaction in auto_balance_query.iter() {
+        if *interaction == Interaction::Pressed {
+            game_state::toggle_workforce_auto_balance(&mut game_state);
+        }
+    }
+    for (interaction, button) in building_query.iter() {
+        if *interaction == Interaction::Pressed {
+            game_state::cycle_building_work_priority(&mut game_state, &button.0);
+        }
+    }
+    for (interaction, button) in specialist_priority_query.iter() {
+        if *interaction == Interaction::Pressed {
+            game_state::move_specialist_priority(&mut game_state, button.0, button.1);
+        }
+    }
+    for interaction in auto_assign_query.iter() {
+        if *interaction == Interaction::Pressed {
+            game_state::toggle_specialist_auto_assign(&mut game_state);
+        }
+    }
+    for (interaction, button) in lock_query.iter() {
+        if *interaction == Interaction::Pressed {
+            game_state::toggle_manual_lock(&mut game_state, &button.0);
+        }
+    }
+}